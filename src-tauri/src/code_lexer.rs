@@ -0,0 +1,483 @@
+use std::{collections::HashMap, fs};
+
+use crate::data::get_lexer_key;
+
+/// how a language writes comments, strings and preprocessor lines
+struct LexerSyntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// `'` only opens a char literal when it does not follow a word, `1'000` is a number
+    char_quote: Option<char>,
+    /// c++ `R"delim(...)delim"`
+    raw_strings: bool,
+    /// `#` at the start of a line is a directive
+    preprocessor: bool,
+    /// `"""..."""` runs over several lines, so does python `'''...'''`
+    triple_quotes: bool,
+    /// rust `'a` is a lifetime, a char literal closes right after its char
+    lifetimes: bool,
+    /// rust `r"..."` and `r#"..."#`, nothing is escaped inside
+    hash_raw_strings: bool,
+    /// javascript `` `...${x}...` `` runs over several lines, its `${}` is blanked too
+    template_strings: bool,
+    /// c# `@"..."` runs over several lines, `""` is a quote inside it
+    verbatim_strings: bool,
+    /// go `` `...` `` runs over several lines, nothing is escaped inside
+    raw_backticks: bool,
+}
+
+const C_SYNTAX: LexerSyntax = LexerSyntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    char_quote: Some('\''),
+    raw_strings: false,
+    preprocessor: true,
+    triple_quotes: false,
+    lifetimes: false,
+    hash_raw_strings: false,
+    template_strings: false,
+    verbatim_strings: false,
+    raw_backticks: false,
+};
+
+const CPP_SYNTAX: LexerSyntax = LexerSyntax {
+    raw_strings: true,
+    ..C_SYNTAX
+};
+
+const PYTHON_SYNTAX: LexerSyntax = LexerSyntax {
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    char_quote: None,
+    raw_strings: false,
+    preprocessor: false,
+    triple_quotes: true,
+    lifetimes: false,
+    hash_raw_strings: false,
+    template_strings: false,
+    verbatim_strings: false,
+    raw_backticks: false,
+};
+
+const RUST_SYNTAX: LexerSyntax = LexerSyntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    char_quote: Some('\''),
+    raw_strings: false,
+    preprocessor: false,
+    triple_quotes: false,
+    lifetimes: true,
+    hash_raw_strings: true,
+    template_strings: false,
+    verbatim_strings: false,
+    raw_backticks: false,
+};
+
+const SCRIPT_SYNTAX: LexerSyntax = LexerSyntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    char_quote: None,
+    raw_strings: false,
+    preprocessor: false,
+    triple_quotes: false,
+    lifetimes: false,
+    hash_raw_strings: false,
+    template_strings: true,
+    verbatim_strings: false,
+    raw_backticks: false,
+};
+
+/// java and kotlin, their text blocks are `"""..."""`
+const JVM_SYNTAX: LexerSyntax = LexerSyntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    char_quote: Some('\''),
+    raw_strings: false,
+    preprocessor: false,
+    triple_quotes: true,
+    lifetimes: false,
+    hash_raw_strings: false,
+    template_strings: false,
+    verbatim_strings: false,
+    raw_backticks: false,
+};
+
+/// c#, its raw strings are `"""..."""`
+const CSHARP_SYNTAX: LexerSyntax = LexerSyntax {
+    triple_quotes: true,
+    verbatim_strings: true,
+    ..C_SYNTAX
+};
+
+/// go, `'` opens a rune
+const GO_SYNTAX: LexerSyntax = LexerSyntax {
+    preprocessor: false,
+    raw_backticks: true,
+    ..C_SYNTAX
+};
+
+fn get_syntax(language_key: &str) -> Option<&'static LexerSyntax> {
+    match language_key {
+        "c" => Some(&C_SYNTAX),
+        "cpp" => Some(&CPP_SYNTAX),
+        "py" => Some(&PYTHON_SYNTAX),
+        "rs" => Some(&RUST_SYNTAX),
+        "js" | "ts" => Some(&SCRIPT_SYNTAX),
+        "java" | "kt" => Some(&JVM_SYNTAX),
+        "cs" => Some(&CSHARP_SYNTAX),
+        "go" => Some(&GO_SYNTAX),
+        _ => None,
+    }
+}
+
+/// one `#if` .. `#endif` block, only the `taken` branch is kept
+struct Branch {
+    current: usize,
+    taken: usize,
+}
+
+/// returns `file_text` with every char that is not code replaced by a space
+///
+/// comments, the inside of string and char literals, preprocessor lines and
+/// the branches of `#if` blocks that are not followed are blanked, new lines
+/// and the quotes themselves are kept, so byte offsets and line numbers stay
+/// the same as in `file_text`
+///
+/// of every `#if` block only the first branch is followed, or the second one
+/// for `#if 0`, so unbalanced braces across `#else` can not break the scopes
+pub fn mask_code(file_path: &str, file_text: &str) -> String {
+    let syntax = match get_lexer_key(file_path).as_deref().and_then(get_syntax) {
+        Some(s) => s,
+        None => return file_text.to_string(),
+    };
+
+    let chars = file_text.chars().collect::<Vec<char>>();
+    let mut masked = chars.clone();
+    let mut branches: Vec<Branch> = Vec::new();
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        let active = branches.iter().all(|b| b.current == b.taken);
+
+        if line_start && c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if syntax.preprocessor && line_start && c == '#' {
+            let end = directive_end(&chars, i);
+            let directive = chars[i + 1..end].iter().collect::<String>();
+            follow_directive(&directive, &mut branches);
+            mask_range(&mut masked, i, end);
+            i = end;
+            continue;
+        }
+        line_start = false;
+
+        if !active {
+            mask_range(&mut masked, i, i + 1);
+            i += 1;
+            continue;
+        }
+
+        if syntax
+            .line_comments
+            .iter()
+            .any(|start| starts_with_at(&chars, i, start))
+        {
+            let end = find_from(&chars, i, "\n").unwrap_or(chars.len());
+            mask_range(&mut masked, i, end);
+            i = end;
+            continue;
+        }
+        if let Some((start, end_marker)) = syntax.block_comment {
+            if starts_with_at(&chars, i, start) {
+                let end = find_from(&chars, i + start.len(), end_marker)
+                    .map(|e| e + end_marker.len())
+                    .unwrap_or(chars.len());
+                mask_range(&mut masked, i, end);
+                i = end;
+                continue;
+            }
+        }
+        if syntax.raw_strings && c == '"' && is_raw_string_prefix(&chars, i) {
+            if let Some(end) = raw_string_end(&chars, i) {
+                mask_range(&mut masked, i + 1, end - 1);
+                i = end;
+                continue;
+            }
+        }
+        if syntax.verbatim_strings && c == '"' && is_verbatim_string_prefix(&chars, i) {
+            let end = verbatim_string_end(&chars, i);
+            mask_range(&mut masked, i + 1, end.saturating_sub(1).max(i + 1));
+            i = end;
+            continue;
+        }
+        if syntax.triple_quotes && syntax.quotes.contains(&c) {
+            let triple = [c; 3].iter().collect::<String>();
+            if starts_with_at(&chars, i, &triple) {
+                let end = find_from(&chars, i + 3, &triple)
+                    .map(|e| e + 3)
+                    .unwrap_or(chars.len());
+                mask_range(&mut masked, i + 3, end.saturating_sub(3).max(i + 3));
+                i = end;
+                continue;
+            }
+        }
+        if syntax.hash_raw_strings && c == '"' {
+            if let Some(end) = hash_raw_string_end(&chars, i) {
+                mask_range(&mut masked, i + 1, end);
+                i = end + 1;
+                continue;
+            }
+        }
+        if syntax.raw_backticks && c == '`' {
+            let end = find_from(&chars, i + 1, "`")
+                .map(|e| e + 1)
+                .unwrap_or(chars.len());
+            mask_range(&mut masked, i + 1, end.saturating_sub(1).max(i + 1));
+            i = end;
+            continue;
+        }
+        if syntax.template_strings && c == '`' {
+            let end = template_string_end(&chars, i);
+            mask_range(&mut masked, i + 1, end.saturating_sub(1).max(i + 1));
+            i = end;
+            continue;
+        }
+        let is_lifetime =
+            syntax.lifetimes && chars.get(i + 1) != Some(&'\\') && chars.get(i + 2) != Some(&'\'');
+        let is_char_quote =
+            Some(c) == syntax.char_quote && (i == 0 || !is_word_char(chars[i - 1])) && !is_lifetime;
+        if syntax.quotes.contains(&c) || is_char_quote {
+            let end = literal_end(&chars, i);
+            mask_range(&mut masked, i + 1, end.saturating_sub(1).max(i + 1));
+            i = end;
+            continue;
+        }
+
+        i += 1;
+    }
+
+    // a blanked multi byte char becomes one space per byte to keep the offsets
+    let mut code_text = String::with_capacity(file_text.len());
+    for (c, m) in chars.iter().zip(masked.iter()) {
+        if c == m {
+            code_text.push(*c);
+        } else {
+            code_text.extend(std::iter::repeat(' ').take(c.len_utf8()));
+        }
+    }
+    code_text
+}
+
+/// a file of the project, read and masked once for every pass over it
+pub struct SourceFile {
+    pub text: String,
+    /// what `mask_code` leaves of `text`
+    pub code: String,
+}
+
+impl SourceFile {
+    pub fn read(file_path: &String) -> Option<SourceFile> {
+        let text = fs::read_to_string(file_path).ok()?;
+        let code = mask_code(file_path, &text);
+        Some(SourceFile { text, code })
+    }
+}
+
+/// file -> its text and code, the files that can not be read are left out
+pub type SourceFiles = HashMap<String, SourceFile>;
+
+/// reads and masks every file
+pub fn read_sources(all_files: &[&String]) -> SourceFiles {
+    all_files
+        .iter()
+        .filter_map(|file| Some((file.to_string(), SourceFile::read(file)?)))
+        .collect()
+}
+
+fn follow_directive(directive: &str, branches: &mut Vec<Branch>) {
+    let directive = directive.trim_start();
+    let name_end = directive
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(directive.len());
+    let (name, rest) = directive.split_at(name_end);
+
+    match name {
+        "if" | "ifdef" | "ifndef" => branches.push(Branch {
+            current: 0,
+            // `#if 0` is a commented out block, its `#else` is the live code
+            taken: if name == "if" && rest.trim() == "0" {
+                1
+            } else {
+                0
+            },
+        }),
+        "elif" | "elifdef" | "elifndef" | "else" => {
+            if let Some(b) = branches.last_mut() {
+                b.current += 1;
+            }
+        }
+        // an `#endif` without its `#if` is ignored
+        "endif" => {
+            branches.pop();
+        }
+        _ => {}
+    }
+}
+
+/// end of a directive, lines ending with `\` continue it
+fn directive_end(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    while i < chars.len() {
+        if chars[i] == '\n' {
+            let mut before = i;
+            while before > start && chars[before - 1] == '\r' {
+                before -= 1;
+            }
+            if before > start && chars[before - 1] == '\\' {
+                i += 1;
+                continue;
+            }
+            return i;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// index right after the closing quote, an unterminated literal ends with its line
+fn literal_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '\n' => return i,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// index right after the closing `` ` ``, new lines do not end a template string
+fn template_string_end(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '`' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// `@"`, `@$"` or `$@"`
+fn is_verbatim_string_prefix(chars: &[char], quote: usize) -> bool {
+    match quote.checked_sub(1).map(|p| chars[p]) {
+        Some('@') => true,
+        Some('$') => quote >= 2 && chars[quote - 2] == '@',
+        _ => false,
+    }
+}
+
+/// index right after the closing `"` of a verbatim string, `""` does not close it
+fn verbatim_string_end(chars: &[char], quote: usize) -> usize {
+    let mut i = quote + 1;
+    while i < chars.len() {
+        if chars[i] == '"' {
+            if chars.get(i + 1) == Some(&'"') {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// `R"`, `u8R"`, `LR"`, `uR"` or `UR"`
+fn is_raw_string_prefix(chars: &[char], quote: usize) -> bool {
+    if quote == 0 || chars[quote - 1] != 'R' {
+        return false;
+    }
+    let mut prefix_start = quote - 1;
+    while prefix_start > 0 && is_word_char(chars[prefix_start - 1]) {
+        prefix_start -= 1;
+    }
+    let prefix = chars[prefix_start..quote - 1].iter().collect::<String>();
+    ["", "u8", "u", "U", "L"].contains(&prefix.as_str())
+}
+
+/// index right after `)delim"`
+fn raw_string_end(chars: &[char], quote: usize) -> Option<usize> {
+    let open = (quote + 1..chars.len().min(quote + 18)).find(|i| chars[*i] == '(')?;
+    let delimiter = chars[quote + 1..open].iter().collect::<String>();
+    let closing = format!("){}\"", delimiter);
+    find_from(chars, open + 1, &closing).map(|e| e + closing.chars().count())
+}
+
+/// index of the closing `"` of `r"..."` or `r#"..."#`, the `#`s after it stay
+fn hash_raw_string_end(chars: &[char], quote: usize) -> Option<usize> {
+    let mut r = quote;
+    while r > 0 && chars[r - 1] == '#' {
+        r -= 1;
+    }
+    let hashes = quote - r;
+    if r == 0 || chars[r - 1] != 'r' {
+        return None;
+    }
+    // `r"`, `br"`, but not `bar"`
+    let mut prefix_start = r - 1;
+    if prefix_start > 0 && chars[prefix_start - 1] == 'b' {
+        prefix_start -= 1;
+    }
+    if prefix_start > 0 && is_word_char(chars[prefix_start - 1]) {
+        return None;
+    }
+    let closing = format!("\"{}", "#".repeat(hashes));
+    Some(find_from(chars, quote + 1, &closing).unwrap_or(chars.len()))
+}
+
+fn mask_range(masked: &mut [char], start: usize, end: usize) {
+    for i in start..end.min(masked.len()) {
+        if masked[i] != '\n' && masked[i] != '\r' {
+            masked[i] = ' ';
+        }
+    }
+}
+
+fn starts_with_at(chars: &[char], i: usize, pattern: &str) -> bool {
+    let mut j = i;
+    for p in pattern.chars() {
+        if j >= chars.len() || chars[j] != p {
+            return false;
+        }
+        j += 1;
+    }
+    true
+}
+
+fn find_from(chars: &[char], from: usize, pattern: &str) -> Option<usize> {
+    (from..chars.len()).find(|i| starts_with_at(chars, *i, pattern))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
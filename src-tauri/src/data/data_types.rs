@@ -66,4 +66,140 @@ pub static DATA_TYPES: phf::Map<&'static str, &'static [&'static str]> = phf_map
         "double",
         "long double",
     ],
+    "py" => &[
+        "int",
+        "float",
+        "complex",
+        "bool",
+        "str",
+        "bytes",
+        "bytearray",
+        "list",
+        "tuple",
+        "dict",
+        "set",
+        "frozenset",
+        "range",
+        "object",
+        "None",
+    ],
+    "rs" => &[
+        "i8",
+        "i16",
+        "i32",
+        "i64",
+        "i128",
+        "isize",
+        "u8",
+        "u16",
+        "u32",
+        "u64",
+        "u128",
+        "usize",
+        "f32",
+        "f64",
+        "bool",
+        "char",
+        "str",
+        "String",
+        "()",
+    ],
+    "ts" => &[
+        "number",
+        "bigint",
+        "string",
+        "boolean",
+        "symbol",
+        "object",
+        "any",
+        "unknown",
+        "never",
+        "void",
+        "undefined",
+        "null",
+    ],
+    "js" => &[
+        "number",
+        "bigint",
+        "string",
+        "boolean",
+        "symbol",
+        "object",
+        "undefined",
+        "null",
+    ],
+    "java" => &[
+        "byte",
+        "short",
+        "int",
+        "long",
+        "float",
+        "double",
+        "boolean",
+        "char",
+        "void",
+        "String",
+    ],
+    "kt" => &[
+        "Byte",
+        "Short",
+        "Int",
+        "Long",
+        "UByte",
+        "UShort",
+        "UInt",
+        "ULong",
+        "Float",
+        "Double",
+        "Boolean",
+        "Char",
+        "String",
+        "Unit",
+        "Any",
+        "Nothing",
+    ],
+    "cs" => &[
+        "bool",
+        "byte",
+        "sbyte",
+        "char",
+        "decimal",
+        "double",
+        "float",
+        "int",
+        "uint",
+        "nint",
+        "nuint",
+        "long",
+        "ulong",
+        "short",
+        "ushort",
+        "object",
+        "string",
+        "void",
+        "dynamic",
+    ],
+    "go" => &[
+        "bool",
+        "string",
+        "int",
+        "int8",
+        "int16",
+        "int32",
+        "int64",
+        "uint",
+        "uint8",
+        "uint16",
+        "uint32",
+        "uint64",
+        "uintptr",
+        "byte",
+        "rune",
+        "float32",
+        "float64",
+        "complex64",
+        "complex128",
+        "error",
+        "any",
+    ],
 };
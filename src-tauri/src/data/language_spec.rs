@@ -0,0 +1,270 @@
+use super::{
+    data_types, regex_access_child, regex_assignments, regex_class, regex_fun, regex_function_call,
+    regex_lambda, regex_object,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+/// how the files of one language are found and read
+///
+/// the built in languages are made of the `regex_*` tables, more can be
+/// described in `.toml` or `.json` files, one language per file, a file with
+/// the key of a known language only overrides what it sets
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct LanguageSpec {
+    /// key the tables and the special cases of the language are found by, `cpp`, `py`...
+    pub key: String,
+    /// name ctags uses for the language, `C++`, `Python`...
+    pub name: String,
+    /// extensions of its files, without the `.`
+    pub extensions: Vec<String>,
+    /// extensions of its headers, their definitions live in a source file of the same name,
+    /// a header is read as the language whether `extensions` lists it or not
+    pub header_extensions: Vec<String>,
+    /// its blocks are opened by a `:` and end with their indentation, not with a closing brace,
+    /// unset keeps the one of the language it overrides, braces for a new one
+    pub indented_blocks: Option<bool>,
+    /// built in language whose comments and literals it shares, none are masked when unset
+    pub lexer: Option<String>,
+    /// built in language whose import statements it shares
+    pub imports: Option<String>,
+    /// patterns whose first group is an imported path, relative to the file or
+    /// to the project, read when `imports` is unset
+    pub import_patterns: Vec<String>,
+    /// `import_patterns` built when the spec is loaded
+    #[serde(skip)]
+    pub import_regexes: Vec<Regex>,
+    /// folders its includes are searched in after the ones of its compile
+    /// command, relative to the project
+    pub include_paths: Vec<String>,
+    /// `NAME` or `NAME=value`, defined for files no compile command overrides them for
+    pub defines: Vec<String>,
+    pub class: Vec<String>,
+    pub function: Vec<String>,
+    pub object: Vec<String>,
+    pub assignment: Vec<String>,
+    pub lambda: Vec<String>,
+    pub function_call: Vec<String>,
+    pub access_child: Vec<String>,
+    pub data_types: Vec<String>,
+}
+
+impl LanguageSpec {
+    /// a built in language, its tables are the ones stored under `key`
+    fn built_in(key: &str, name: &str, extensions: &[&str]) -> LanguageSpec {
+        let table = |map: &phf::Map<&'static str, &'static [&'static str]>| {
+            map.get(key)
+                .map_or(Vec::new(), |t| t.iter().map(|s| s.to_string()).collect())
+        };
+        LanguageSpec {
+            key: key.to_string(),
+            name: name.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+            lexer: Some(key.to_string()),
+            imports: Some(key.to_string()),
+            class: table(&regex_class::CLASS),
+            function: table(&regex_fun::FUNCTION),
+            object: table(&regex_object::OBJECT),
+            assignment: table(&regex_assignments::ASSIGNMENT),
+            lambda: table(&regex_lambda::LAMBDA),
+            function_call: table(&regex_function_call::FUNCTION_CALL),
+            access_child: table(&regex_access_child::ACCESS_CHILD),
+            data_types: table(&data_types::DATA_TYPES),
+            ..Default::default()
+        }
+    }
+
+    /// `self` with everything `other` sets, its extensions are added to the known ones
+    fn merged(&self, other: &LanguageSpec) -> LanguageSpec {
+        let pick = |own: &Vec<String>, new: &Vec<String>| match new.is_empty() {
+            true => own.clone(),
+            false => new.clone(),
+        };
+        let union = |own: &Vec<String>, new: &Vec<String>| {
+            let mut all = own.clone();
+            all.extend(new.iter().filter(|e| !own.contains(e)).cloned());
+            all
+        };
+        LanguageSpec {
+            key: self.key.clone(),
+            name: match other.name.is_empty() {
+                true => self.name.clone(),
+                false => other.name.clone(),
+            },
+            extensions: union(&self.extensions, &other.extensions),
+            header_extensions: union(&self.header_extensions, &other.header_extensions),
+            indented_blocks: other.indented_blocks.or(self.indented_blocks),
+            lexer: other.lexer.clone().or(self.lexer.clone()),
+            imports: other.imports.clone().or(self.imports.clone()),
+            import_patterns: pick(&self.import_patterns, &other.import_patterns),
+            import_regexes: match other.import_patterns.is_empty() {
+                true => self.import_regexes.clone(),
+                false => other.import_regexes.clone(),
+            },
+            include_paths: union(&self.include_paths, &other.include_paths),
+            defines: union(&self.defines, &other.defines),
+            class: pick(&self.class, &other.class),
+            function: pick(&self.function, &other.function),
+            object: pick(&self.object, &other.object),
+            assignment: pick(&self.assignment, &other.assignment),
+            lambda: pick(&self.lambda, &other.lambda),
+            function_call: pick(&self.function_call, &other.function_call),
+            access_child: pick(&self.access_child, &other.access_child),
+            data_types: pick(&self.data_types, &other.data_types),
+        }
+    }
+}
+
+fn built_in_specs() -> Vec<LanguageSpec> {
+    vec![
+        LanguageSpec::built_in("c", "C", &["c"]),
+        LanguageSpec {
+            header_extensions: ["h", "hh", "hpp", "hxx"]
+                .iter()
+                .map(|e| e.to_string())
+                .collect(),
+            ..LanguageSpec::built_in("cpp", "C++", &["cpp", "cc", "cxx", "h", "hh", "hpp", "hxx"])
+        },
+        LanguageSpec {
+            indented_blocks: Some(true),
+            ..LanguageSpec::built_in("py", "Python", &["py", "pyi"])
+        },
+        LanguageSpec::built_in("rs", "Rust", &["rs"]),
+        LanguageSpec::built_in("js", "JavaScript", &["js", "jsx", "mjs", "cjs"]),
+        LanguageSpec::built_in("ts", "TypeScript", &["ts", "tsx", "mts", "cts"]),
+        LanguageSpec::built_in("java", "Java", &["java"]),
+        LanguageSpec::built_in("kt", "Kotlin", &["kt", "kts"]),
+        LanguageSpec::built_in("cs", "C#", &["cs"]),
+        LanguageSpec::built_in("go", "Go", &["go"]),
+        // only its includes are read
+        LanguageSpec {
+            lexer: None,
+            ..LanguageSpec::built_in("php", "PHP", &["php"])
+        },
+    ]
+}
+
+lazy_static! {
+    /// the known languages, a spec loaded later wins an extension over the earlier ones
+    static ref LANGUAGES: RwLock<Vec<Arc<LanguageSpec>>> =
+        RwLock::new(built_in_specs().into_iter().map(Arc::new).collect());
+}
+
+/// the spec a file is read with
+pub fn get_language_spec(file_path: &str) -> Option<Arc<LanguageSpec>> {
+    let extension = Path::new(file_path).extension()?.to_str()?;
+    LANGUAGES
+        .read()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|s| {
+            s.extensions
+                .iter()
+                .chain(&s.header_extensions)
+                .any(|e| e == extension)
+        })
+        .cloned()
+}
+
+/// the spec known by `key`
+pub fn get_spec_by_key(key: &str) -> Option<Arc<LanguageSpec>> {
+    LANGUAGES
+        .read()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|s| s.key == key)
+        .cloned()
+}
+
+/// is any language reading `extension` as a header
+pub fn is_header_extension(extension: &str) -> bool {
+    LANGUAGES
+        .read()
+        .unwrap()
+        .iter()
+        .any(|s| s.header_extensions.iter().any(|e| e == extension))
+}
+
+/// resets the known languages to the built in ones, then adds every spec of
+/// `<project>/.structura/languages`
+///
+/// a spec that can not be read is reported and skipped, the others are still loaded
+pub fn load_language_specs(project_path: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut loaded: Vec<LanguageSpec> = Vec::new();
+    let folder = Path::new(project_path).join(".structura").join("languages");
+    let mut files = fs::read_dir(&folder)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    // the order specs override each other in does not depend on the file system
+    files.sort();
+    for file in files {
+        match read_language_spec(&file) {
+            Ok(Some(spec)) => loaded.push(spec),
+            Ok(None) => {}
+            Err(e) => errors.push(format!("{} : {}", file.display(), e)),
+        }
+    }
+
+    let mut specs = built_in_specs();
+    for spec in loaded {
+        match specs.iter().position(|s| s.key == spec.key) {
+            Some(i) => {
+                let merged = specs[i].merged(&spec);
+                // moved last, the extensions it adds win over the other languages
+                specs.remove(i);
+                specs.push(merged);
+            }
+            None => specs.push(spec),
+        }
+    }
+    *LANGUAGES.write().unwrap() = specs.into_iter().map(Arc::new).collect();
+    errors
+}
+
+/// `Ok(None)` for a file that is not a spec
+fn read_language_spec(file: &Path) -> Result<Option<LanguageSpec>, String> {
+    let parse: fn(&str) -> Result<LanguageSpec, String> =
+        match file.extension().and_then(|e| e.to_str()) {
+            Some("toml") => |text| toml::from_str(text).map_err(|e| e.to_string()),
+            Some("json") => |text| serde_json::from_str(text).map_err(|e| e.to_string()),
+            _ => return Ok(None),
+        };
+    let text = fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let mut spec = parse(&text)?;
+    if spec.key.is_empty() {
+        return Err("the spec has no `key`".to_string());
+    }
+    let invalid =
+        |pattern: &String, e: regex::Error| format!("invalid pattern {} : {}", pattern, e);
+    spec.import_regexes = spec
+        .import_patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| invalid(pattern, e)))
+        .collect::<Result<Vec<Regex>, String>>()?;
+    let patterns = [
+        &spec.class,
+        &spec.function,
+        &spec.object,
+        &spec.assignment,
+        &spec.lambda,
+        &spec.function_call,
+        &spec.access_child,
+    ];
+    for pattern in patterns.into_iter().flatten() {
+        Regex::new(pattern).map_err(|e| invalid(pattern, e))?;
+    }
+    Ok(Some(spec))
+}
@@ -1,4 +1,5 @@
 mod data_types;
+mod language_spec;
 mod regex_access_child;
 mod regex_assignments;
 mod regex_class;
@@ -10,61 +11,108 @@ mod regex_lambda;
 pub mod regex_macros;
 mod regex_object;
 
+use regex::Regex;
 use std::path::Path;
 
+pub use language_spec::load_language_specs;
+use language_spec::{get_language_spec, get_spec_by_key, is_header_extension, LanguageSpec};
+
 pub fn get_language(file_path: &String) -> Option<&str> {
     Path::new(file_path)
         .extension()
         .and_then(|ext| ext.to_str())
 }
 
+/// key of the language tables a file is read with
+pub fn get_language_key(file_path: &str) -> Option<String> {
+    get_language_spec(file_path).map(|s| s.key.clone())
+}
+
+/// a c / c++ header, its definitions live in a source file of the same name
+pub fn is_header(file_path: &String) -> bool {
+    get_language(file_path).map_or(false, is_header_extension)
+}
+
+/// name ctags uses for the language behind a key, used to filter scans
+pub fn get_language_name(key: &str) -> Option<String> {
+    get_spec_by_key(key)
+        .map(|s| s.name.clone())
+        .filter(|n| !n.is_empty())
+}
+
+/// the blocks of the language are opened by a `:` and end with their indentation,
+/// not with a closing brace
+pub fn has_indented_blocks(file_path: &str) -> bool {
+    get_language_spec(file_path).map_or(false, |s| s.indented_blocks == Some(true))
+}
+
+/// built in language whose comments and literals the file is masked with
+pub fn get_lexer_key(file_path: &str) -> Option<String> {
+    get_language_spec(file_path)?.lexer.clone()
+}
+
+/// built in language whose import statements the file is read with, and the
+/// patterns that find them when there is none
+pub fn get_import_reader(file_path: &str) -> (Option<String>, Vec<Regex>) {
+    match get_language_spec(file_path) {
+        Some(s) => (s.imports.clone(), s.import_regexes.clone()),
+        None => (None, Vec::new()),
+    }
+}
+
+/// include folders and defines configured for the language of the file
+pub fn get_include_config(file_path: &str) -> (Vec<String>, Vec<String>) {
+    match get_language_spec(file_path) {
+        Some(s) => (s.include_paths.clone(), s.defines.clone()),
+        None => (Vec::new(), Vec::new()),
+    }
+}
+
 fn get_data_for_extension<'a>(
     file_path: &'a String,
-    map: &'a phf::Map<&'static str, &'static [&'static str]>,
-) -> (Option<&'a str>, Option<&'a &'static [&'static str]>) {
-    if let Some(extension) = get_language(file_path) {
-        match extension {
-            "c" => (Some(extension), map.get("c")),
-            "cpp" | "cc" | "cxx" | "h" | "hpp" => (Some(extension), map.get("cpp")),
-            _ => (None, None),
+    table: fn(&LanguageSpec) -> &Vec<String>,
+) -> (Option<&'a str>, Option<Vec<String>>) {
+    match (get_language(file_path), get_language_spec(file_path)) {
+        // a language without the table is read as if it was unknown
+        (Some(extension), Some(spec)) if !table(&spec).is_empty() => {
+            (Some(extension), Some(table(&spec).clone()))
         }
-    } else {
-        (None, None)
+        (extension, _) => (extension, None),
     }
 }
 
-pub fn get_data_types(file_path: &String) -> Option<&&[&str]> {
-    get_data_for_extension(file_path, &data_types::DATA_TYPES).1
+pub fn get_data_types(file_path: &String) -> Option<Vec<String>> {
+    get_data_for_extension(file_path, |s| &s.data_types).1
 }
 
-pub fn get_regex_access_child(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_access_child::ACCESS_CHILD)
+pub fn get_regex_access_child(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.access_child)
 }
 
-pub fn get_regex_function_call(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_function_call::FUNCTION_CALL)
+pub fn get_regex_function_call(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.function_call)
 }
 
-pub fn get_regex_assignments(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_assignments::ASSIGNMENT)
+pub fn get_regex_assignments(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.assignment)
 }
 
-pub fn get_regex_class(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_class::CLASS)
+pub fn get_regex_class(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.class)
 }
 
-pub fn get_regex_fun(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_fun::FUNCTION)
+pub fn get_regex_fun(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.function)
 }
 
 // pub fn get_regex_interface(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
 //     get_data_for_extension(file_path, &regex_interface::INTERFACE)
 // }
 
-pub fn get_regex_lambda(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_lambda::LAMBDA)
+pub fn get_regex_lambda(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.lambda)
 }
 
-pub fn get_regex_object(file_path: &String) -> (Option<&str>, Option<&&[&str]>) {
-    get_data_for_extension(file_path, &regex_object::OBJECT)
+pub fn get_regex_object(file_path: &String) -> (Option<&str>, Option<Vec<String>>) {
+    get_data_for_extension(file_path, |s| &s.object)
 }
@@ -11,6 +11,46 @@ pub static ACCESS_CHILD: phf::Map<&'static str, &'static [&'static str]> = phf::
             fun_call_or_word!(), possible_spaces!() , "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), or!(), r"->", possible_spaces!(), fun_call_or_word!(), possible_spaces!(), ")+"
         )
     ],
+    "rs" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "py" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "ts" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "js" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "java" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "kt" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "cs" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
+    "go" => &[
+        concat!(
+            fun_call_or_word!(), "(", possible_spaces!(), r"\.", possible_spaces!(), fun_call_or_word!(), ")+"
+        )
+    ],
 };
 
 // word = [a-zA-Z_][a-zA-Z0-9_]*
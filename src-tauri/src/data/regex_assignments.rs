@@ -29,4 +29,32 @@ pub static ASSIGNMENT: phf::Map<&'static str, &'static [&'static str]> = phf::ph
             possible_spaces!(), r";"
         ),
     ],
+    "rs" => &[
+        // `==` is a comparison and `=>` an arm of a match
+        r"([\w\.]+)\s*=[^=>][^;]*;",
+    ],
+    "py" => &[
+        // one statement per line, `==` is a comparison and `x: int = 3` is an attribute
+        r"(?m)^[ \t]*([\w\.]+)[ \t]*=[^=\n].*$",
+    ],
+    // the `;` is optional, `===` is a comparison and `=>` an arrow function
+    "ts" => &[
+        r"([\w\.]+)[ \t]*=[^=>][^;\n]*;?",
+    ],
+    "js" => &[
+        r"([\w\.]+)[ \t]*=[^=>][^;\n]*;?",
+    ],
+    "java" => &[
+        r"([\w\.]+)\s*=[^=][^;]*;",
+    ],
+    // one statement per line
+    "kt" => &[
+        r"(?m)([\w\.]+)[ \t]*=[^=>\n][^;\n]*",
+    ],
+    "cs" => &[
+        r"([\w\.]+)\s*=[^=>][^;]*;",
+    ],
+    "go" => &[
+        r"(?m)([\w\.]+)[ \t]*:?=[^=\n][^\n]*",
+    ],
 };
@@ -1,14 +1,113 @@
 use crate::*;
 
+// (?P<keyword>)(?P<name>)(?P<parents>), the name is missing for
+// `typedef struct { .. } Name;` and for anonymous namespaces, the parents of an
+// enum are its underlying type, a `template<..>` clause in front is part of the
+// match, a python class ends with the `:` opening its block, the `<T>` of a rust
+// type is its `generics`, a `body` group is the bracket the scope of the type
+// starts at when it is not the end of the match
 pub static CLASS: phf::Map<&'static str, &'static [&'static str]> = phf::phf_map! {
     "c" => &[
         concat!(
-            r"class",r"\s+", word!(), r"(?:\s*:\s*([\w\s,]+))?", possible_spaces!(), r"\{",
+            r"\b(?:typedef\s+)?(?P<keyword>struct|union|enum)",
+            r"(?:\s+(?P<name>", word!(), r"))?", possible_spaces!(), r"\{",
         ),
     ],
     "cpp" => &[
         concat!(
-            r"class",r"\s+", word!(), r"(?:\s*:\s*([\w\s,]+))?", possible_spaces!(), r"\{",
+            r"\b(?:template\s*<[^;{]*>\s*)?",
+            r"(?:typedef\s+)?(?P<keyword>class|struct|union|enum\s+class|enum\s+struct|enum|namespace)",
+            r"(?:\s+(?P<name>", word!(), r"(?:::", word!(), r")*))?", r"(?:\s+final)?",
+            r"(?:\s*:\s*(?P<parents>[\w\s,:<>\*&]+))?", possible_spaces!(), r"\{",
+        ),
+    ],
+    "py" => &[
+        concat!(
+            r"(?m)^[ \t]*(?P<keyword>class)[ \t]+(?P<name>", word!(), r")",
+            r"[ \t]*(?:\((?P<parents>[^)]*)\))?[ \t]*:",
+        ),
+    ],
+    "rs" => &[
+        // `trait Shape: Debug + Clone {`, a tuple struct opens its `(` instead
+        concat!(
+            r"\b(?:pub(?:\([^)]*\))?\s+)?(?P<keyword>struct|enum|union|trait|mod)\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?",
+            r"\s*(?::\s*(?P<parents>[^{;]+?))?\s*(?:where\s[^{;]*?)?[\{\(]",
+        ),
+        // `impl<T> Display for Wrapper<T> {` names the type, the trait is its parent
+        concat!(
+            r"(?m)^[ \t]*(?:unsafe\s+)?(?P<keyword>impl)(?P<generics><[^{;]*?>)?\s+",
+            r"(?:(?P<parents>[\w:]+(?:<[^{;]*?>)?)\s+for\s+)?(?P<name>[\w:]+(?:<[^{;]*?>)?)",
+            r"\s*(?:where\s[^{;]*?)?\{",
+        ),
+    ],
+    // `export default abstract class Dog<T> extends Animal implements Pet, Named {`,
+    // the `implements` list holds more parents
+    "ts" => &[
+        concat!(
+            r"\b(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:const\s+)?",
+            r"(?P<keyword>class|interface|enum|namespace|module)\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?",
+            r"(?:\s+extends\s+(?P<parents>[^{;]+?))?(?:\s+implements\s+(?P<implements>[^{;]+?))?\s*\{",
+        ),
+    ],
+    "js" => &[
+        concat!(
+            r"\b(?:export\s+)?(?:default\s+)?(?P<keyword>class)\s+(?P<name>", word!(), r")",
+            r"(?:\s+extends\s+(?P<parents>[^{;]+?))?\s*\{",
+        ),
+    ],
+    // `public final class Dog<T> extends Animal implements Pet, Named {`, a record
+    // lists its components first, `@interface` is an annotation
+    "java" => &[
+        concat!(
+            r"(?:(?:public|protected|private|static|final|abstract|sealed|non-sealed|strictfp)\s+)*@?",
+            r"\b(?P<keyword>class|interface|enum|record)\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?",
+            r"(?:\s*\([^)]*\))?(?:\s+extends\s+(?P<parents>[^{;]+?))?(?:\s+implements\s+(?P<implements>[^{;]+?))?",
+            r"(?:\s+permits\s+[^{;]+?)?\s*\{",
+        ),
+    ],
+    // `data class Dog(val name: String) : Animal(name), Pet {`, a base list only
+    // goes on to the next line after a `,`
+    "kt" => &[
+        concat!(
+            r"\b(?:(?:public|private|protected|internal|open|abstract|sealed|data|inner|annotation|value|inline|final)\s+)*",
+            r"(?P<keyword>enum\s+class|class|interface|object)\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?",
+            r"(?:\s*(?:(?:public|private|protected|internal)\s+)?constructor)?(?:\s*\((?:[^()]|\([^()]*\))*\))?",
+            r"(?:\s*:\s*(?P<parents>(?:[^{;\n]|,\s*\n)+?))?(?:\s+where\s[^{;]*?)?\s*\{",
+        ),
+        // `data class Point(val x: Int, val y: Int) : Shape()` has no body, its
+        // constructor is its scope
+        concat!(
+            r"(?m)^[ \t]*(?:(?:public|private|protected|internal|open|abstract|sealed|data|inner|annotation|value|inline|final)\s+)*",
+            r"(?P<keyword>enum\s+class|class)\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?",
+            r"(?:\s*(?:(?:public|private|protected|internal)\s+)?constructor)?\s*(?P<body>\()(?:[^()]|\([^()]*\))*\)",
+            r"(?:[ \t]*:[ \t]*(?P<parents>(?:[^{;\n]|,\s*\n)+?))?[ \t]*$",
+        ),
+    ],
+    // `public partial class Dog<T> : Animal, IPet where T : new() {`, a record
+    // can have its constructor in the head, a file scoped `namespace Zoo;` is a
+    // scope up to the end of the file, it is matched first to be the owner of
+    // the types after it
+    "cs" => &[
+        concat!(
+            r"\b(?P<keyword>namespace)\s+(?P<name>", word!(), r"(?:\.", word!(), r")*)\s*(?P<body>;)",
+        ),
+        concat!(
+            r"\b(?:(?:public|protected|private|internal|static|abstract|sealed|unsafe|new|readonly|ref|file)\s+)*",
+            r"(?P<partial>partial\s+)?(?P<keyword>record\s+struct|record\s+class|record|class|struct|interface|enum|namespace)\s+",
+            r"(?P<name>", word!(), r"(?:\.", word!(), r")*)(?P<generics><[^{;]*?>)?(?:\s*\((?:[^()]|\([^()]*\))*\))?",
+            r"(?:\s*:\s*(?P<parents>[^{;]+?))?(?:\s+where\s[^{;]*?)?\s*\{",
+        ),
+        // `public record Point(int X, int Y) : Shape;` has no body, its constructor is its scope
+        concat!(
+            r"\b(?:(?:public|protected|private|internal|static|abstract|sealed|unsafe|new|readonly|ref|file)\s+)*",
+            r"(?P<partial>partial\s+)?(?P<keyword>record\s+struct|record\s+class|record)\s+(?P<name>", word!(), r")(?P<generics><[^{;(]*?>)?",
+            r"\s*(?P<body>\()(?:[^()]|\([^()]*\))*\)(?:\s*:\s*(?P<parents>[^{;]+?))?\s*;",
+        ),
+    ],
+    // `type Repo[T any] struct {`, the types it embeds are its parents
+    "go" => &[
+        concat!(
+            r"\btype\s+(?P<name>", word!(), r")(?P<generics>\[[^\]\n]*\])?\s+(?P<keyword>struct|interface)\s*\{",
         ),
     ],
 };
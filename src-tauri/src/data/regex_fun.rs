@@ -8,7 +8,95 @@ pub static FUNCTION: phf::Map<&'static str, &'static [&'static str]> = phf::phf_
     ],
     "cpp" => &[
         concat!(
-            r"(\w[\w\s\*&:<>]*)\s+(\w+)", possible_spaces!(), anything_inside_brackets!(), possible_spaces!(), r"(const override|const|)", possible_spaces!(), r"\{"
+            // `int Mammal::getAge(int y) const {` is named `Mammal::getAge`
+            r"(\w[\w\s\*&:<>,]*)\s+((?:\w+::)*~?\w+)", possible_spaces!(), anything_inside_brackets!(), possible_spaces!(), r"(const override|const|)", possible_spaces!(), r"\{"
+        ),
+    ],
+    "rs" => &[
+        concat!(
+            // `pub async fn get<T: Into<String>>(&self, key: T) -> Option<&str> where .. {`
+            r"\b(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern(?:\s+\x22[^\x22]*\x22)?)\s+)*",
+            r"fn\s+(?P<name>", word!(), r")(?P<generics><[^{;]*?>)?", possible_spaces!(),
+            r"\((?P<args>[^{;]*?)\)", possible_spaces!(), r"(?:->\s*(?P<ret>[^{;]+?))?\s*(?:where\s[^{;]*?)?\{"
+        ),
+    ],
+    "py" => &[
+        concat!(
+            // `async def fetch(self, url: str) -> bytes:`, the return type follows the args
+            r"\b(?:async\s+)?def\s+", word!(), possible_spaces!(), anything_inside_brackets!(), possible_spaces!(), r"(?:->[^:\n]+)?:"
+        ),
+    ],
+    // the args may hold one level of `()` and `{}`, `({ id }: Props)`
+    "ts" => &[
+        concat!(
+            // `export async function load<T>(id: string): Promise<T> {`
+            r"\b(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*(?P<name>", word!(), r")(?P<generics><[^{;(]*?>)?", possible_spaces!(),
+            r"\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)", possible_spaces!(), r"(?::\s*(?P<ret>[^{;=]+?))?\s*\{"
+        ),
+        concat!(
+            // `private async load(id: string): Promise<void> {` in a class or an object
+            r"(?m)^[ \t]*(?:(?:public|private|protected|static|readonly|async|abstract|override|get|set)\s+)*\*?",
+            r"(?P<name>#?", word!(), r")(?P<generics><[^{;(]*?>)?", possible_spaces!(),
+            r"\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)", possible_spaces!(), r"(?::\s*(?P<ret>[^{;=]+?))?\s*\{"
+        ),
+        concat!(
+            // `export const load = async (id: string): Promise<T> => {` is named after the const
+            r"\b(?:export\s+)?(?:const|let|var)\s+(?P<name>", word!(), r")\s*(?::\s*[^=;]+?)?\s*=\s*(?:async\s+)?(?P<generics><[^{;(]*?>)?", possible_spaces!(),
+            r"(?:\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)|(?P<param>", word!(), r"))", possible_spaces!(),
+            r"(?::\s*(?P<ret>[^{;=]+?))?\s*=>\s*[\{\(]"
+        ),
+    ],
+    "js" => &[
+        concat!(
+            r"\b(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*(?P<name>", word!(), r")", possible_spaces!(),
+            r"\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)", possible_spaces!(), r"\{"
+        ),
+        concat!(
+            r"(?m)^[ \t]*(?:(?:static|async|get|set)\s+)*\*?(?P<name>#?", word!(), r")", possible_spaces!(),
+            r"\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)", possible_spaces!(), r"\{"
+        ),
+        concat!(
+            r"\b(?:export\s+)?(?:const|let|var)\s+(?P<name>", word!(), r")\s*=\s*(?:async\s+)?",
+            r"(?:\((?P<args>(?:[^(){};]|\([^()]*\)|\{[^{}]*\})*)\)|(?P<param>", word!(), r"))\s*=>\s*[\{\(]"
+        ),
+    ],
+    // `@Override public <T> List<T> find(String name) throws IOException {`, a
+    // constructor has no return type
+    "java" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:@", word!(), r"(?:\([^)]*\))?\s+)*",
+            r"(?:(?:public|protected|private|static|final|abstract|synchronized|native|default|strictfp)\s+)*",
+            r"(?:(?P<generics><[^{;(]*?>)\s*)?(?:(?P<ret>[\w.]+(?:<[^{;()]*?>)?(?:\[\])*)\s+)?(?P<name>", word!(), r")", possible_spaces!(),
+            r"\((?P<args>[^)]*)\)", possible_spaces!(), r"(?:throws\s+[\w.,\s]+?)?\s*\{"
+        ),
+    ],
+    // `override suspend fun <T> load(id: Int, done: () -> Unit): List<T> {`, the
+    // receiver of an extension function is left out
+    "kt" => &[
+        concat!(
+            r"\b(?:(?:public|private|protected|internal|open|override|abstract|final|suspend|inline|operator|infix|tailrec|external)\s+)*",
+            r"fun\s+(?P<generics><[^{;(]*?>)?\s*(?:[\w.<>?]+\.)?(?P<name>", word!(), r")", possible_spaces!(),
+            r"\((?P<args>(?:[^()]|\([^()]*\))*)\)", possible_spaces!(), r"(?::\s*(?P<ret>[^{=\n]+?))?\s*\{"
+        ),
+    ],
+    // `public async Task<List<T>> Load<T>(int id) where T : new() {`, a
+    // constructor can call `: base(name)` before its body
+    "cs" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:\[[^\]\n]*\]\s*)*(?:(?:public|protected|private|internal|static|virtual|override|abstract|sealed|async|extern|unsafe|new|partial|readonly)\s+)*",
+            r"(?:(?P<ret>[\w.]+(?:<[^{;()]*?>)?\??(?:\[[,\s]*\])*\??)\s+)?(?P<name>", word!(), r")(?P<generics><[^{;(]*?>)?", possible_spaces!(),
+            r"\((?P<args>[^)]*)\)", possible_spaces!(), r"(?:where\s[^{;]*?)?(?::\s*(?:base|this)\s*\([^)]*\)\s*)?\{"
+        ),
+    ],
+    // `func (r *Repo[T]) Save(ctx context.Context) (int, error) {` is a method of
+    // `Repo`, a method of an interface has no body, its `(..)` is its scope
+    "go" => &[
+        concat!(
+            r"\bfunc\s+(?:\((?P<receiver>[^)]*)\)\s*)?(?P<name>", word!(), r")(?P<generics>\[[^\]\n]*\])?", possible_spaces!(),
+            r"\((?P<args>(?:[^()]|\([^()]*\))*)\)[ \t]*(?P<ret>[^{\n]*?)\s*\{"
+        ),
+        concat!(
+            r"(?m)^[ \t]+(?P<name>", word!(), r")[ \t]*(?P<body>\()(?P<args>(?:[^()]|\([^()]*\))*)\)[ \t]*(?P<ret>[^{}\n]*?)[ \t]*$"
         ),
     ],
 };
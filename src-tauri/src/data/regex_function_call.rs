@@ -11,4 +11,44 @@ pub static FUNCTION_CALL: phf::Map<&'static str, &'static [&'static str]> = phf:
             word!(), possible_spaces!(), r"\("
         )
     ],
+    "rs" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "py" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "ts" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "js" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "java" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "kt" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "cs" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
+    "go" => &[
+        concat!(
+            word!(), possible_spaces!(), r"\("
+        )
+    ],
 };
@@ -11,4 +11,47 @@ pub static LAMBDA: phf::Map<&'static str, &'static [&'static str]> = phf::phf_ma
             r"\[(|(\&*\**", possible_spaces!() ,r"(", word!() ,r"\s+)+", word!() ,r")(", possible_spaces!() ,r",", possible_spaces!() ,r"(\&*\**", possible_spaces!() ,r"(", word!() ,r"\s+)+", word!() ,r"))*)\]\((|(\&*\**", possible_spaces!() ,r"(", word!() ,r"\s+)+", word!() ,r")(", possible_spaces!() ,r",", possible_spaces!() ,r"(\&*\**", possible_spaces!() ,r"(", word!() ,r"\s+)+", word!() ,r"))*)\)", possible_spaces!() ,r"\{",
         )
     ],
+    "rs" => &[
+        // `move |a: u32, b| {`, the `|` of a closure follows a `(`, `,` or `=`
+        r"(?:[(,=]\s*|\bmove\s+)\|([^|\n]*)\|(?:\s*\{)?",
+    ],
+    "py" => &[
+        // `lambda a, b: a + b` has no block, its args live in the enclosing scope
+        r"\blambda\b([^:\n]*):",
+    ],
+    // `(a, b) => {`, `x => x * 2` and `function (a) {`
+    "ts" => &[
+        concat!(
+            r"(?:\(((?:[^()]|\([^()]*\))*)\)|\b(", word!(), r"))\s*(?::\s*[^=;{()]+?)?\s*=>(?:\s*[\{\(])?"
+        ),
+        r"\bfunction\s*\*?\s*\(((?:[^()]|\([^()]*\))*)\)\s*\{",
+    ],
+    "js" => &[
+        concat!(
+            r"(?:\(((?:[^()]|\([^()]*\))*)\)|\b(", word!(), r"))\s*=>(?:\s*[\{\(])?"
+        ),
+        r"\bfunction\s*\*?\s*\(((?:[^()]|\([^()]*\))*)\)\s*\{",
+    ],
+    // `(a, b) -> {` and `x -> x * 2`
+    "java" => &[
+        concat!(
+            r"(?:\(([^()]*)\)|\b(", word!(), r"))\s*->(?:\s*\{)?"
+        ),
+    ],
+    // `{ a, b -> a + b }`, a lambda is its own scope, `{ it * 2 }` has no args
+    "kt" => &[
+        concat!(
+            r"\{\s*((?:", word!(), r"(?:\s*:\s*[\w<>?.]+)?\s*,\s*)*", word!(), r"(?:\s*:\s*[\w<>?.]+)?)\s*->"
+        ),
+    ],
+    "cs" => &[
+        concat!(
+            r"(?:\(([^()]*)\)|\b(", word!(), r"))\s*=>(?:\s*\{)?"
+        ),
+    ],
+    "go" => &[
+        concat!(
+            r"\bfunc\s*\(([^()]*)\)[^{\n]*\{"
+        ),
+    ],
 };
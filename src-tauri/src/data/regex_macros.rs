@@ -5,6 +5,14 @@ macro_rules! word {
     };
 }
 
+/// `std::vector<Animal>`, no space is allowed before the `<` so `a < b` is not a type
+#[macro_export]
+macro_rules! type_ref {
+    () => {
+        concat!(word!(), r"(?:::", word!(), r")*(?:<[\w\s:,<>\*&]*>)?")
+    };
+}
+
 #[macro_export]
 macro_rules! possible_spaces {
     () => {
@@ -6,8 +6,104 @@ pub static OBJECT: phf::Map<&'static str, &'static [&'static str]> = phf::phf_ma
     ],
     "cpp" => &[
         concat!(
-            r"(", word!(), r"\s+" , r")", "+",
+            r"(", word!(), r"\s+" , r")", "*",
+            type_ref!(), r"[\*&]*\s+",
             word!(), possible_spaces!(), either_or!(",", "=", ";", r"\)")
         )
     ],
+    // the name, the type and the value are captured by name, a `field` is only
+    // an object inside the body of a type
+    "rs" => &[
+        // `pub name: String,`
+        concat!(
+            r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?\s+)?(?P<field>", word!(), r")\s*:\s*(?P<type>[\w:<>&'\[\]\(\) ,]+?)\s*,?[ \t]*$"
+        ),
+        // `let mut dogs: Vec<Dog> = Vec::new();`
+        concat!(
+            r"\blet\s+(?:mut\s+)?(?P<name>", word!(), r")\s*(?::\s*(?P<type>[^=;]+?))?\s*(?:=\s*(?P<value>[^;]+))?;"
+        ),
+        // `static COUNT: AtomicUsize = ..`
+        concat!(
+            r"\b(?:const|static)\s+(?:mut\s+)?(?P<name>", word!(), r")\s*:\s*(?P<type>[^=;]+?)\s*="
+        ),
+    ],
+    // python declares one per line
+    "py" => &[
+        // `self.name: str = name` in a method is an attribute of the class
+        concat!(
+            r"(?m)^[ \t]*self\.(?P<name>", word!(), r")",
+            r"[ \t]*(?::[ \t]*(?P<type>[^=\n]+?))?[ \t]*=[ \t]*(?P<value>[^=\n].*)$"
+        ),
+        // `count: int = 0`
+        concat!(
+            r"(?m)^[ \t]*(?P<name>", word!(), r")[ \t]*:[ \t]*(?P<type>[^=\n]+?)[ \t]*(?:=(?P<value>.*))?$"
+        ),
+        // `count = 0`
+        concat!(
+            r"(?m)^[ \t]*(?P<name>", word!(), r")[ \t]*=[ \t]*(?P<value>[^=\n].*)$"
+        ),
+    ],
+    // a `field` is a member of a class or an interface, `private name?: string = "";`
+    "ts" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:(?:public|private|protected|static|readonly|declare|abstract|override)\s+)*(?P<field>#?", word!(), r")[?!]?",
+            r"[ \t]*(?::[ \t]*(?P<type>(?:[^=;\n]|=>)+?))?[ \t]*(?:=[ \t]*(?P<value>[^>;\n][^;\n]*?))?[ \t]*[;,]?[ \t]*$"
+        ),
+        // `const dogs: Dog[] = [];`
+        concat!(
+            r"(?m)\b(?:const|let|var)\s+(?P<name>", word!(), r")[ \t]*(?::[ \t]*(?P<type>(?:[^=;\n]|=>)+?))?",
+            r"[ \t]*(?:=[ \t]*(?P<value>[^>;\n][^;\n]*)|;|$)"
+        ),
+    ],
+    "js" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:static\s+)?(?P<field>#?", word!(), r")[ \t]*(?:=[ \t]*(?P<value>[^;\n]+?))?[ \t]*;?[ \t]*$"
+        ),
+        concat!(
+            r"\b(?:const|let|var)\s+(?P<name>", word!(), r")[ \t]*(?:=[ \t]*(?P<value>[^;\n]+))?"
+        ),
+    ],
+    // `private final List<Dog> dogs = new ArrayList<>();`, `var` takes the type of the value
+    "java" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:@", word!(), r"(?:\([^)]*\))?\s+)*(?:(?:public|protected|private|static|final|transient|volatile)\s+)*",
+            r"(?:var\s+|(?P<type>[\w.]+(?:<[^;=(){}]*>)?(?:\[\])*)\s+)(?P<name>", word!(), r")\s*(?:=\s*(?P<value>[^;]+))?;"
+        ),
+    ],
+    "kt" => &[
+        // `private val name: String = ""`
+        concat!(
+            r"(?m)^[ \t]*(?:@", word!(), r"(?:\([^)]*\))?\s+)*(?:(?:public|private|protected|internal|override|open|const|lateinit|abstract|final)\s+)*",
+            r"(?:val|var)\s+(?P<name>", word!(), r")[ \t]*(?::[ \t]*(?P<type>(?:[^=\n{,)<]|<[^>\n]*>)+?))?[ \t]*(?:=[ \t]*(?P<value>[^\n]+?))?[ \t]*(?:\bby\b[^\n]*)?$"
+        ),
+        // `class Dog(val name: String)` declares `name` on the class
+        concat!(
+            r"[(,]\s*(?:@", word!(), r"\s+)*(?:(?:public|private|protected|internal|override|open)\s+)*",
+            r"(?:val|var)\s+(?P<property>", word!(), r")\s*:\s*(?P<type>[^,=)\n]+)"
+        ),
+    ],
+    "cs" => &[
+        concat!(
+            r"(?m)^[ \t]*(?:\[[^\]\n]*\]\s*)*(?:(?:public|protected|private|internal|static|readonly|const|volatile|new|required|virtual|override|abstract|sealed)\s+)*",
+            r"(?:var\s+|(?P<type>[\w.]+(?:<[^;=(){}]*>)?\??(?:\[[,\s]*\])*\??)\s+)(?P<name>", word!(), r")\s*(?:=\s*(?P<value>[^;]+))?;"
+        ),
+        // `public string Name { get; private set; }` and `public int Age => age;`
+        concat!(
+            r"(?m)^[ \t]*(?:\[[^\]\n]*\]\s*)*(?:(?:public|protected|private|internal|static|readonly|const|volatile|new|required|virtual|override|abstract|sealed)\s+)*",
+            r"(?P<type>[\w.]+(?:<[^;=(){}]*>)?\??(?:\[[,\s]*\])*\??)\s+(?P<name>", word!(), r")\s*(?:\{\s*(?:(?:public|protected|private|internal)\s+)*(?:get|set|init)\b|=>)"
+        ),
+    ],
+    "go" => &[
+        // `Name string` and `Pets []*Dog `json:"pets"`` in a struct
+        concat!(
+            r"(?m)^[ \t]+(?P<field>", word!(), r")[ \t]+(?P<type>[*\[\]\w.]+(?:\[[^\]\n]*\])?[*\w.]*)[ \t]*(?:`[^`\n]*`)?[ \t]*$"
+        ),
+        // `var count int = 3`, `const max = 10` and `dog := &Dog{}`
+        concat!(
+            r"(?m)^[ \t]*(?:var|const)[ \t]+(?P<name>", word!(), r")(?:[ \t]+(?P<type>[*\[\]\w.]+))?(?:[ \t]*=[ \t]*(?P<value>[^\n]+?))?[ \t]*$"
+        ),
+        concat!(
+            r"(?m)^[ \t]*(?P<name>", word!(), r")[ \t]*:=[ \t]*(?P<value>[^\n]+?)[ \t]*$"
+        ),
+    ],
 };
@@ -1,19 +1,37 @@
 use std::{
-    collections::{HashMap},
+    collections::{HashMap, HashSet},
     usize,
 };
 
 pub mod program_tag;
 pub use program_tag::{ClassType, ProgramTag};
 
+mod compile_commands;
+mod packages;
 mod read_imports;
+use compile_commands::CompileDatabase;
+pub use packages::ExternalDependency;
+use packages::PackageRoots;
 use read_imports::Import;
 
-use crate::tag_entry::{ClassEntry, FunctionEntry, ObjectEntry, ScopeEntry};
+use crate::code_lexer::SourceFiles;
+use crate::data::get_language_key;
+use crate::qualified_names::{
+    lookup_candidates, parent_path, read_using_directives, resolve, UsingDirectives,
+};
+use crate::tag_entry::{ClassEntry, ClassKind, FunctionEntry, ObjectEntry, ScopeEntry};
+
+/// file -> imported file -> the names taken from it, `(name, local name)`
+pub type ImportedNames = HashMap<usize, HashMap<usize, Vec<(String, String)>>>;
+
+/// file -> local name -> the `(file, tag)` it was imported as, or the
+/// `(file, index)` of what else `symbol_imports` was asked to find
+pub type SymbolImports = HashMap<usize, HashMap<String, (usize, usize)>>;
 
 pub fn evaluate_all_hard_data<'a>(
     project_path: &String,
     all_files: &'a Vec<&'a String>,
+    sources: &SourceFiles,
     all_hard_data: HashMap<
         &'a String,
         (
@@ -27,8 +45,24 @@ pub fn evaluate_all_hard_data<'a>(
     HashMap<usize, Vec<usize>>,
     HashMap<usize, Vec<ProgramTag>>,
     HashMap<(usize, usize), Vec<(usize, usize)>>,
+    ImportedNames,
+    SymbolImports,
+    Vec<ExternalDependency>,
+    HashMap<usize, Vec<usize>>,
 ) {
-    let raw_imports = read_all_imports(project_path, all_files);
+    let (raw_imports, imported_names, external_dependencies, same_package) =
+        read_all_imports(project_path, all_files, sources);
+    // a class reached through a chain of headers is as visible as an included one,
+    // so is a class of the package of the file
+    let mut visible_imports = include_closure(all_files, &raw_imports);
+    for (f, seen_files) in same_package {
+        let visible = visible_imports.entry(f).or_default();
+        for seen in seen_files {
+            if !visible.contains(&seen) {
+                visible.push(seen);
+            }
+        }
+    }
 
     println!("\n\n-------- all_files --------\n\n");
     for (f, f_p) in all_files.iter().enumerate() {
@@ -45,53 +79,122 @@ pub fn evaluate_all_hard_data<'a>(
 
     let mut all_tags: HashMap<usize, Vec<ProgramTag>> = HashMap::new();
     let mut children_tags: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    // functions defined out of line, `int Mammal::getAge() {`, whose class is not in their file
+    let mut out_of_line: Vec<(usize, usize)> = Vec::new();
+    // `partial class Dog` of c#, the parts of a type that are merged
+    let mut partial_parts: Vec<(usize, usize)> = Vec::new();
+    // file, go type -> whether each of its parents is embedded as a pointer, `*Animal`
+    let mut pointer_embeds: HashMap<(usize, String), Vec<bool>> = HashMap::new();
 
     // initial formation of tags list and tags hierarchy
     for (f, file_path) in all_files.iter().enumerate() {
         if let Some(file_hard_data) = all_hard_data.get(file_path) {
             let mut scope_to_class_tag = HashMap::new();
+            let mut name_to_class_tag = HashMap::new();
 
             // add the entry
             file_hard_data.1.iter().enumerate().for_each(|(i, c)| {
+                // `Pen<Animal>` is the parent `Pen` and the generic arg `Animal`
+                let mut parents = Vec::new();
+                let mut generic_args = Vec::new();
+                for (_, p) in &c.parents {
+                    let (parent, args) = ClassType::with_generic_args(&file_path, p);
+                    parents.push(parent);
+                    generic_args.extend(args);
+                }
                 all_tags
                     .entry(f)
                     .or_insert_with(Vec::new)
                     .push(ProgramTag::Class {
                         name: c.name.clone(),
-                        parents: c
-                            .parents
-                            .iter()
-                            .map(|p| ClassType::new(&file_path, p.clone()))
-                            .collect(),
+                        qualified_name: c.qualified_name.clone(),
+                        kind: c.kind,
+                        parents,
+                        parents_access: c.parents.iter().map(|(a, _)| *a).collect(),
+                        template_params: c.template_params.clone(),
+                        generic_args,
+                        span: c.span,
                     });
-                scope_to_class_tag.insert(c.class_scope, i);
+
+                if c.partial {
+                    partial_parts.push((f, i));
+                }
+                if c.parents.iter().any(|(_, p)| p.starts_with('*')) {
+                    pointer_embeds.insert(
+                        (f, c.qualified_name.clone()),
+                        c.parents.iter().map(|(_, p)| p.starts_with('*')).collect(),
+                    );
+                }
+                // types nested in a namespace or another type are its children
+                if let Some(parent_class) = scope_to_class_tag.get(&c.parent_scope) {
+                    children_tags
+                        .entry((f, *parent_class))
+                        .or_default()
+                        .push((f, i));
+                }
+                // a plain typedef has no scope of its own
+                if c.class_scope != c.parent_scope {
+                    scope_to_class_tag.insert(c.class_scope, i);
+                }
+                // the tags file may name the owner of a member with or without its namespaces
+                name_to_class_tag.insert(c.qualified_name.clone(), i);
+                name_to_class_tag.entry(c.name.clone()).or_insert(i);
             });
             file_hard_data.2.iter().for_each(|fun| {
+                let (class, generic_args) =
+                    ClassType::with_generic_args(&file_path, &fun.class_name);
                 all_tags
                     .entry(f)
                     .or_insert_with(Vec::new)
                     .push(ProgramTag::Function {
                         name: fun.name.clone(),
-                        class: ClassType::new(&file_path, fun.class_name.clone()),
+                        qualified_name: fun.qualified_name.clone(),
+                        class,
+                        access: fun.access,
+                        template_params: fun.template_params.clone(),
+                        generic_args,
+                        span: fun.span,
+                        signature: fun.signature.clone(),
+                        pointer_receiver: fun.pointer_receiver,
                     });
 
-                if let Some(parent_class) = scope_to_class_tag.get(&fun.parent_scope) {
+                let parent_class = match &fun.qualifier {
+                    // `void Mammal::feed() {` inside `namespace zoo {` is a child of `zoo::Mammal`
+                    Some(_) => name_to_class_tag.get(parent_path(&fun.qualified_name)),
+                    // the scope walk wins, the owner from the tags file covers what it missed
+                    None => scope_to_class_tag
+                        .get(&fun.parent_scope)
+                        .or_else(|| fun.owner.as_ref().and_then(|o| name_to_class_tag.get(o))),
+                };
+                if let Some(parent_class) = parent_class {
                     children_tags
                         .entry((f, parent_class.clone()))
                         .or_default()
                         .push((f, all_tags[&f].len() - 1));
+                } else if fun.qualifier.is_some() || fun.owner.is_some() {
+                    out_of_line.push((f, all_tags[&f].len() - 1));
                 }
             });
             file_hard_data.3.iter().for_each(|ob| {
+                let (class, generic_args) =
+                    ClassType::with_generic_args(&file_path, &ob.class_name);
                 all_tags
                     .entry(f)
                     .or_insert_with(Vec::new)
                     .push(ProgramTag::Object {
                         name: ob.name.clone(),
-                        class: ClassType::new(&file_path, ob.class_name.clone()),
+                        qualified_name: ob.qualified_name.clone(),
+                        class,
+                        access: ob.access,
+                        generic_args,
+                        span: ob.span,
                     });
 
-                if let Some(parent_class) = scope_to_class_tag.get(&ob.parent_scope) {
+                // the scope walk wins, the owner from the tags file covers what it missed
+                if let Some(parent_class) = scope_to_class_tag
+                    .get(&ob.parent_scope)
+                    .or_else(|| ob.owner.as_ref().and_then(|o| name_to_class_tag.get(o)))
+                {
                     children_tags
                         .entry((f, parent_class.clone()))
                         .or_default()
@@ -103,40 +206,195 @@ pub fn evaluate_all_hard_data<'a>(
         }
     }
 
+    // template parameters of a tag and of the classes around it are not classes to look for
+    for file_tags in all_tags.values_mut() {
+        let class_params = file_tags
+            .iter()
+            .filter(|t| t.is_class() && !t.get_template_params().is_empty())
+            .map(|t| {
+                (
+                    t.get_qualified_name().clone(),
+                    t.get_template_params().to_vec(),
+                )
+            })
+            .collect::<Vec<(String, Vec<String>)>>();
+        for t in file_tags.iter_mut() {
+            let mut params = t.get_template_params().to_vec();
+            let context = t.get_lookup_context();
+            for (class_name, class_params) in &class_params {
+                if context == class_name || context.starts_with(&format!("{}::", class_name)) {
+                    params.extend(class_params.iter().cloned());
+                }
+            }
+            if !params.is_empty() {
+                t.mark_template_params(&params);
+            }
+        }
+    }
+
+    // the classes are the same for the passes below, what a file sees is read once
+    let mut file_lookups: HashMap<usize, FileLookup> = HashMap::new();
+
+    // an out of line definition is a child of its class, wherever that is declared
+    for (f, tag_i) in out_of_line {
+        let (usings, visible_classes) = file_lookups
+            .entry(f)
+            .or_insert_with(|| file_lookup(all_files, sources, &all_tags, &visible_imports, f));
+        let visible_names = visible_classes
+            .iter()
+            .map(|(_, _, name)| name.as_str())
+            .collect::<Vec<&str>>();
+        // `ns::Mammal::getAge` is looked up as `ns::Mammal` from the global scope
+        let candidates = lookup_candidates("", all_tags[&f][tag_i].get_lookup_context(), usings);
+        if let Some(v) = resolve(&candidates, &visible_names, |_| true) {
+            children_tags
+                .entry((visible_classes[v].0, visible_classes[v].1))
+                .or_default()
+                .push((f, tag_i));
+        }
+    }
+
+    // c# declares a type once unless it is `partial`, the later parts of a type
+    // are merged into its first one like an `impl` block is
+    let mut first_parts: Vec<(&String, &[String])> = Vec::new();
+    let mut later_parts: Vec<(usize, usize)> = Vec::new();
+    for &(f, tag_i) in &partial_parts {
+        let t = &all_tags[&f][tag_i];
+        let part = (t.get_qualified_name(), t.get_template_params());
+        match first_parts.contains(&part) {
+            true => later_parts.push((f, tag_i)),
+            false => first_parts.push(part),
+        }
+    }
+    for (f, tag_i) in later_parts {
+        if let Some(ProgramTag::Class { kind, .. }) =
+            all_tags.get_mut(&f).and_then(|t| t.get_mut(tag_i))
+        {
+            *kind = ClassKind::Implementation;
+        }
+    }
+
+    // `impl Display for Dog {` gives its methods and its `Display` base to `Dog`,
+    // wherever that is declared
+    let implementations = all_tags
+        .iter()
+        .flat_map(|(f, file_tags)| {
+            file_tags
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.is_implementation())
+                .map(move |(tag_i, _)| (*f, tag_i))
+        })
+        .collect::<Vec<(usize, usize)>>();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (f, tag_i) in implementations {
+        let implementation = all_tags[&f][tag_i].clone();
+        let target = {
+            let (usings, visible_classes) = file_lookups
+                .entry(f)
+                .or_insert_with(|| file_lookup(all_files, sources, &all_tags, &visible_imports, f));
+            let visible_names = visible_classes
+                .iter()
+                .map(|(_, _, name)| name.as_str())
+                .collect::<Vec<&str>>();
+            let candidates = lookup_candidates(
+                implementation.get_lookup_context(),
+                implementation.get_name(),
+                usings,
+            );
+            let is_type = |v: usize| {
+                !all_tags[&visible_classes[v].0][visible_classes[v].1].is_implementation()
+            };
+            resolve(&candidates, &visible_names, is_type)
+                .map(|v| (visible_classes[v].0, visible_classes[v].1))
+        };
+        if let Some((target_f, target_i)) = target {
+            if let Some(t) = all_tags
+                .get_mut(&target_f)
+                .and_then(|t| t.get_mut(target_i))
+            {
+                t.extend_parents(&implementation);
+            }
+            if let Some(children) = children_tags.remove(&(f, tag_i)) {
+                children_tags
+                    .entry((target_f, target_i))
+                    .or_default()
+                    .extend(children);
+            }
+            merged.push((f, tag_i));
+        }
+    }
+    // a merged block is part of its type, not a type of its own
+    drop_tags(&mut all_tags, &mut children_tags, &merged);
+
+    // `from models import User` points at the `User` of `models.py`, the outermost
+    // declaration of the name, a type before what shares its name
+    let symbol_imports = symbol_imports(&imported_names, |file, name| {
+        all_tags
+            .get(&file)?
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.get_name() == name)
+            .min_by_key(|(_, t)| {
+                (
+                    t.is_implementation(),
+                    !t.is_class(),
+                    t.get_qualified_name().matches("::").count(),
+                )
+            })
+            .map(|(tag_i, _)| tag_i)
+    });
+
     // trying to create connections between tag_class and some actual class that may exist
     let mut changes: HashMap<(usize, usize), Vec<(usize, usize, usize)>> = HashMap::new();
-    for (f, _) in all_files.iter().enumerate() {
+    for (f, file_path) in all_files.iter().enumerate() {
         let file_tags = match all_tags.get(&f) {
             Some(x) => x,
             None => &Vec::new(),
         };
 
-        let imported_files = match raw_imports.get(&f) {
+        let imported_files = match visible_imports.get(&f) {
             Some(fi) => fi,
             None => &Vec::new(),
         };
+        let usings = read_using_directives(file_path, sources);
 
-        all_tags
+        let visible_classes = visible_classes(&all_tags, f, imported_files);
+        let visible_names = visible_classes
             .iter()
-            // tags from imported files
-            .filter(|(imported_file, _)| imported_files.contains(imported_file))
-            .for_each(|(imported_file, imported_tags)| {
-                for (i_t_i, i_t) in imported_tags.iter().enumerate() {
-                    if !i_t.is_class() {
-                        continue;
-                    }
-                    for (matched_tag_index, t) in file_tags.iter().enumerate() {
-                        t.needed_class().iter().enumerate().for_each(|(i, c)| {
-                            if c.unwrap_or(&"!".to_string()) == i_t.get_name() {
-                                changes
-                                    .entry((f, matched_tag_index))
-                                    .or_insert_with(Vec::new)
-                                    .push((i, *imported_file, i_t_i));
-                            }
-                        });
+            .map(|(_, _, name)| *name)
+            .collect::<Vec<&str>>();
+
+        for (matched_tag_index, t) in file_tags.iter().enumerate() {
+            t.needed_class().iter().enumerate().for_each(|(i, c)| {
+                let query = match c {
+                    Some(q) => q,
+                    None => return,
+                };
+                // a name imported by name is what it was imported as, before any lookup
+                let imported = symbol_imports.get(&f).and_then(|s| s.get(*query));
+                if let Some(&(imported_f, imported_i)) = imported {
+                    if all_tags[&imported_f][imported_i].is_class() {
+                        changes
+                            .entry((f, matched_tag_index))
+                            .or_insert_with(Vec::new)
+                            .push((i, imported_f, imported_i));
+                        return;
                     }
                 }
+                let candidates = lookup_candidates(t.get_lookup_context(), query, &usings);
+                // `typedef struct Node Node;` does not point at itself
+                let not_itself = |v: usize| {
+                    (visible_classes[v].0, visible_classes[v].1) != (f, matched_tag_index)
+                };
+                if let Some(v) = resolve(&candidates, &visible_names, not_itself) {
+                    changes
+                        .entry((f, matched_tag_index))
+                        .or_insert_with(Vec::new)
+                        .push((i, visible_classes[v].0, visible_classes[v].1));
+                }
             });
+        }
 
         // let progress = ((f + 1) as f32 / all_files.len() as f32) * 100.0;
     }
@@ -151,6 +409,19 @@ pub fn evaluate_all_hard_data<'a>(
         });
     });
 
+    // a go type satisfies the interfaces whose methods it has without naming them,
+    // it is listed under every one of them like under a base
+    for (interface, types) in
+        implicit_interfaces(all_files, &all_tags, &children_tags, &pointer_embeds)
+    {
+        let children = children_tags.entry(interface).or_default();
+        for t in types {
+            if !children.contains(&t) {
+                children.push(t);
+            }
+        }
+    }
+
     println!("\n\n---------- file wise tags ----------\n\n");
     for (f, file_path) in all_files.iter().enumerate() {
         println!("for file {}->{}", f, file_path);
@@ -167,7 +438,320 @@ pub fn evaluate_all_hard_data<'a>(
         });
     }
 
-    return (raw_imports, all_tags, children_tags);
+    return (
+        raw_imports,
+        all_tags,
+        children_tags,
+        imported_names,
+        symbol_imports,
+        external_dependencies,
+        visible_imports,
+    );
+}
+
+/// removes the `dropped` tags, the children table follows the new indices and
+/// forgets the dropped ones
+fn drop_tags(
+    all_tags: &mut HashMap<usize, Vec<ProgramTag>>,
+    children_tags: &mut HashMap<(usize, usize), Vec<(usize, usize)>>,
+    dropped: &[(usize, usize)],
+) {
+    if dropped.is_empty() {
+        return;
+    }
+    let dropped = dropped.iter().copied().collect::<HashSet<(usize, usize)>>();
+    // (file, old index) -> new index
+    let mut new_index: HashMap<(usize, usize), usize> = HashMap::new();
+    for (f, file_tags) in all_tags.iter_mut() {
+        let (mut tag_i, mut kept) = (0, 0);
+        file_tags.retain(|_| {
+            let keep = !dropped.contains(&(*f, tag_i));
+            if keep {
+                new_index.insert((*f, tag_i), kept);
+                kept += 1;
+            }
+            tag_i += 1;
+            keep
+        });
+    }
+    *children_tags = children_tags
+        .drain()
+        .filter_map(|((f, tag_i), children)| {
+            let parent = (f, *new_index.get(&(f, tag_i))?);
+            let children = children
+                .into_iter()
+                .filter_map(|(cf, ct)| Some((cf, *new_index.get(&(cf, ct))?)))
+                .collect();
+            Some((parent, children))
+        })
+        .collect();
+}
+
+/// the children table with the private and protected members left out, what
+/// a user of the classes can reach
+pub fn api_children(
+    all_tags: &HashMap<usize, Vec<ProgramTag>>,
+    children_tags: &HashMap<(usize, usize), Vec<(usize, usize)>>,
+) -> HashMap<(usize, usize), Vec<(usize, usize)>> {
+    children_tags
+        .iter()
+        .map(|(parent, children)| {
+            (
+                *parent,
+                children
+                    .iter()
+                    .filter(|(f, t)| all_tags[f][*t].is_public())
+                    .copied()
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// `(interface, types)` of the go interfaces and the types that satisfy them
+///
+/// the method set of a type holds its methods and those of the types it
+/// embeds, a method with a pointer receiver is only in the set of a pointer to
+/// the type, so are the ones a type embedded as a pointer brings, the methods
+/// are matched by name and signature, an empty interface is satisfied by every
+/// type and is left out, so are the types that embed the interface
+fn implicit_interfaces(
+    all_files: &[&String],
+    all_tags: &HashMap<usize, Vec<ProgramTag>>,
+    children_tags: &HashMap<(usize, usize), Vec<(usize, usize)>>,
+    pointer_embeds: &HashMap<(usize, String), Vec<bool>>,
+) -> Vec<((usize, usize), Vec<(usize, usize)>)> {
+    /// the methods of `class`, `(name, signature)`, with the pointer receivers when `pointer`
+    fn method_set<'a>(
+        class: (usize, usize),
+        pointer: bool,
+        all_tags: &'a HashMap<usize, Vec<ProgramTag>>,
+        children_tags: &HashMap<(usize, usize), Vec<(usize, usize)>>,
+        pointer_embeds: &HashMap<(usize, String), Vec<bool>>,
+        seen: &mut Vec<(usize, usize)>,
+        methods: &mut Vec<(&'a str, Option<&'a str>)>,
+    ) {
+        if seen.contains(&class) {
+            return;
+        }
+        seen.push(class);
+        for (f, t) in children_tags.get(&class).into_iter().flatten() {
+            if let ProgramTag::Function {
+                name,
+                signature,
+                pointer_receiver,
+                ..
+            } = &all_tags[f][*t]
+            {
+                if pointer || !pointer_receiver {
+                    methods.push((name.as_str(), signature.as_deref()));
+                }
+            }
+        }
+        if let ProgramTag::Class {
+            qualified_name,
+            parents,
+            ..
+        } = &all_tags[&class.0][class.1]
+        {
+            let embedded_pointers = pointer_embeds.get(&(class.0, qualified_name.clone()));
+            for (p_i, parent) in parents.iter().enumerate() {
+                if let ClassType::Connected(f, t) = parent {
+                    // `*Animal` brings the pointer receivers of `Animal` too
+                    let pointer =
+                        pointer || embedded_pointers.map_or(false, |e| e.get(p_i) == Some(&true));
+                    method_set(
+                        (*f, *t),
+                        pointer,
+                        all_tags,
+                        children_tags,
+                        pointer_embeds,
+                        seen,
+                        methods,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut interfaces = Vec::new();
+    let mut types = Vec::new();
+    for f in
+        (0..all_files.len()).filter(|f| get_language_key(all_files[*f]).as_deref() == Some("go"))
+    {
+        for (t, tag) in all_tags.get(&f).into_iter().flatten().enumerate() {
+            let mut methods = Vec::new();
+            let mut seen = Vec::new();
+            let is_interface = match tag {
+                ProgramTag::Class { kind, .. } => *kind == ClassKind::Interface,
+                _ => continue,
+            };
+            method_set(
+                (f, t),
+                false,
+                all_tags,
+                children_tags,
+                pointer_embeds,
+                &mut seen,
+                &mut methods,
+            );
+            match is_interface {
+                true if !methods.is_empty() => interfaces.push(((f, t), methods)),
+                true => {}
+                // `seen` holds the types it embeds
+                false => types.push(((f, t), methods, seen)),
+            }
+        }
+    }
+
+    // a signature the tags file did not give matches any
+    let satisfies = |methods: &Vec<(&str, Option<&str>)>,
+                     (name, signature): &(&str, Option<&str>)| {
+        methods
+            .iter()
+            .any(|(m, s)| m == name && (s.is_none() || signature.is_none() || s == signature))
+    };
+    interfaces
+        .into_iter()
+        .map(|(interface, wanted)| {
+            let satisfying = types
+                .iter()
+                .filter(|(_, methods, embedded)| {
+                    !embedded.contains(&interface) && wanted.iter().all(|w| satisfies(methods, w))
+                })
+                .map(|(t, _, _)| *t)
+                .collect::<Vec<(usize, usize)>>();
+            (interface, satisfying)
+        })
+        .filter(|(_, satisfying)| !satisfying.is_empty())
+        .collect()
+}
+
+/// file -> the files it sees through its imports, the includes of an included
+/// c / c++ header are followed, a cycle of headers is walked once
+///
+/// what a file of another language imports is not seen by its importers
+pub fn include_closure(
+    all_files: &[&String],
+    raw_imports: &HashMap<usize, Vec<usize>>,
+) -> HashMap<usize, Vec<usize>> {
+    let is_c = |f: usize| matches!(get_language_key(all_files[f]).as_deref(), Some("c" | "cpp"));
+    raw_imports
+        .iter()
+        .map(|(file, imports)| {
+            let mut closure = imports.clone();
+            let mut seen = imports.iter().copied().collect::<HashSet<usize>>();
+            seen.insert(*file);
+            // the direct includes come first, then each level of the chain
+            let mut i = 0;
+            while is_c(*file) && i < closure.len() {
+                let header = closure[i];
+                i += 1;
+                if !is_c(header) {
+                    continue;
+                }
+                for included in raw_imports.get(&header).into_iter().flatten() {
+                    if seen.insert(*included) {
+                        closure.push(*included);
+                    }
+                }
+            }
+            (*file, closure)
+        })
+        .collect()
+}
+
+/// the `using` lines of a file and its visible classes, `(file, tag, qualified name)`
+type FileLookup = (UsingDirectives, Vec<(usize, usize, String)>);
+
+fn file_lookup(
+    all_files: &[&String],
+    sources: &SourceFiles,
+    all_tags: &HashMap<usize, Vec<ProgramTag>>,
+    visible_imports: &HashMap<usize, Vec<usize>>,
+    f: usize,
+) -> FileLookup {
+    let no_imports = vec![];
+    let imported_files = visible_imports.get(&f).unwrap_or(&no_imports);
+    let visible_classes = visible_classes(all_tags, f, imported_files)
+        .into_iter()
+        .map(|(file, tag, name)| (file, tag, name.to_string()))
+        .collect();
+    (
+        read_using_directives(all_files[f], sources),
+        visible_classes,
+    )
+}
+
+/// (file, tag, qualified name) of the classes of file `f` and of the files it imports
+fn visible_classes<'a>(
+    all_tags: &'a HashMap<usize, Vec<ProgramTag>>,
+    f: usize,
+    imported_files: &[usize],
+) -> Vec<(usize, usize, &'a str)> {
+    all_tags
+        .iter()
+        .filter(|(other_file, _)| **other_file == f || imported_files.contains(other_file))
+        .flat_map(|(other_file, other_tags)| {
+            other_tags
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.is_class())
+                .map(move |(t_i, t)| (*other_file, t_i, t.get_qualified_name().as_str()))
+        })
+        .collect()
+}
+
+/// the declarations the names of each file stand for, `file -> local name -> (file, index)`
+///
+/// `declared(file, name)` is the index of what `file` declares as `name`, a
+/// name the file does not declare but imports itself is followed there, so
+/// `export { Graph } from './graph'` leads to the `Graph` of `graph.ts`
+pub fn symbol_imports<D>(imported_names: &ImportedNames, declared: D) -> SymbolImports
+where
+    D: Fn(usize, &str) -> Option<usize>,
+{
+    fn follow<D: Fn(usize, &str) -> Option<usize>>(
+        imported_names: &ImportedNames,
+        declared: &D,
+        file: usize,
+        name: &str,
+        seen: &mut Vec<(usize, String)>,
+    ) -> Option<(usize, usize)> {
+        if seen.contains(&(file, name.to_string())) {
+            return None;
+        }
+        seen.push((file, name.to_string()));
+        if let Some(index) = declared(file, name) {
+            return Some((file, index));
+        }
+        imported_names
+            .get(&file)?
+            .iter()
+            .find_map(|(next_file, names)| {
+                names
+                    .iter()
+                    .filter(|(_, local)| local == name)
+                    .find_map(|(next_name, _)| {
+                        follow(imported_names, declared, *next_file, next_name, seen)
+                    })
+            })
+    }
+
+    let mut edges: SymbolImports = HashMap::new();
+    for (f, files) in imported_names {
+        for (imported_file, names) in files {
+            for (name, local) in names {
+                let mut seen = Vec::new();
+                if let Some(found) =
+                    follow(imported_names, &declared, *imported_file, name, &mut seen)
+                {
+                    edges.entry(*f).or_default().insert(local.clone(), found);
+                }
+            }
+        }
+    }
+    edges
 }
 
 pub fn jsonify_evaluated_data(
@@ -225,24 +809,98 @@ pub fn jsonify_evaluated_data(
     return (imports, tags, children);
 }
 
+/// file -> the files it imports, and what names it takes from each of them,
+/// `(name, local name)`, then the packages imported from outside the project
+/// but the standard library, and the files of its own package it sees without importing them
 pub fn read_all_imports<'a>(
     project_path: &String,
     all_files: &'a Vec<&'a String>,
-) -> HashMap<usize, Vec<usize>> {
+    sources: &SourceFiles,
+) -> (
+    HashMap<usize, Vec<usize>>,
+    ImportedNames,
+    Vec<ExternalDependency>,
+    HashMap<usize, Vec<usize>>,
+) {
     let mut all_imports: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut same_package: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut imported_names: ImportedNames = HashMap::new();
+    let mut external_dependencies: Vec<ExternalDependency> = Vec::new();
+    let namespaces = read_imports::csharp_namespaces(all_files, sources);
+    let compile_database = CompileDatabase::load(project_path);
+    let packages = PackageRoots::load(project_path, all_files);
 
     for (f, file) in all_files.iter().enumerate() {
         println!("{}", file);
-        match read_imports::get_imported_files(project_path, file) {
+        let imports = read_imports::get_imported_files(
+            project_path,
+            file,
+            sources,
+            &namespaces,
+            &compile_database,
+            &packages,
+        );
+        match imports {
             Ok(imports) => {
-                for import in imports {
+                for (import, names) in imports {
                     let import_path = match import {
                         Import::File(path) => path,
                         Import::Module(path) => path,
-                        Import::Package(path) => path,
+                        Import::Package(name) if packages::is_standard_library(file, &name) => {
+                            continue;
+                        }
+                        // a package is a node of its own, not a file of the project
+                        Import::Package(name) => {
+                            let ecosystem = packages::ecosystem(file);
+                            let known = external_dependencies
+                                .iter_mut()
+                                .find(|d| d.name == name && d.ecosystem == ecosystem);
+                            match known {
+                                Some(dependency) if !dependency.importers.contains(&f) => {
+                                    dependency.importers.push(f)
+                                }
+                                Some(_) => {}
+                                None => {
+                                    let mut dependency = ExternalDependency {
+                                        name,
+                                        ecosystem,
+                                        version: None,
+                                        location: None,
+                                        importers: vec![f],
+                                    };
+                                    packages.describe(&mut dependency, file);
+                                    external_dependencies.push(dependency);
+                                }
+                            }
+                            continue;
+                        }
+                        // seen by the classes of the file, not written as an import
+                        Import::SamePackage(path) => {
+                            if let Some(seen) = all_files.iter().position(|i| **i == path) {
+                                let seen_files = same_package.entry(f).or_default();
+                                if !seen_files.contains(&seen) {
+                                    seen_files.push(seen);
+                                }
+                            }
+                            continue;
+                        }
                     };
                     if let Some(import_index) = all_files.iter().position(|i| **i == import_path) {
-                        all_imports.entry(f).or_default().push(import_index);
+                        // `mod zoo;` and `use crate::zoo::Dog;` import the same file
+                        let file_imports = all_imports.entry(f).or_default();
+                        if !file_imports.contains(&import_index) {
+                            file_imports.push(import_index);
+                        }
+                        let file_names = imported_names
+                            .entry(f)
+                            .or_default()
+                            .entry(import_index)
+                            .or_default();
+                        for name in names {
+                            if !file_names.contains(&name) {
+                                file_names.push(name);
+                            }
+                        }
                     }
                 }
             }
@@ -252,5 +910,36 @@ pub fn read_all_imports<'a>(
         }
     }
 
-    all_imports
+    external_dependencies.sort_by(|a, b| (&a.ecosystem, &a.name).cmp(&(&b.ecosystem, &b.name)));
+    (
+        all_imports,
+        imported_names,
+        external_dependencies,
+        same_package,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_header_includes_once() {
+        let files = ["main.c", "a.h", "b.h", "c.h", "util.py", "other.py"].map(|f| f.to_string());
+        let all_files = files.iter().collect::<Vec<&String>>();
+        // `a.h` and `b.h` include each other, `b.h` includes `c.h` too
+        let raw_imports = HashMap::from([
+            (0, vec![1]),
+            (1, vec![2]),
+            (2, vec![1, 3]),
+            (4, vec![5]),
+            (5, vec![0]),
+        ]);
+        let closure = include_closure(&all_files, &raw_imports);
+        assert_eq!(closure[&0], vec![1, 2, 3]);
+        assert_eq!(closure[&1], vec![2, 3]);
+        assert_eq!(closure[&2], vec![1, 3]);
+        // what a python module imports is not seen by its importers
+        assert_eq!(closure[&4], vec![5]);
+    }
 }
@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::data::get_include_config;
+
+/// folders a `compile_commands.json` is looked for in, under the project
+const BUILD_FOLDERS: [&str; 4] = ["", "build", "out", "cmake-build-debug"];
+
+/// how one translation unit is compiled, what its `#include`s are searched in
+#[derive(Debug, Default, Clone)]
+pub struct CompileFlags {
+    /// `-iquote` folders, only searched by `#include "..."`
+    pub quote_dirs: Vec<PathBuf>,
+    /// `-I`, `-isystem` and `-idirafter` folders, in the order they are searched
+    pub include_dirs: Vec<PathBuf>,
+    /// `-D NAME=value` is `Some(value)`, `-U NAME` is `None`
+    pub defines: HashMap<String, Option<String>>,
+    /// `-include` files, read as if included on the first line
+    pub forced_includes: Vec<PathBuf>,
+}
+
+impl CompileFlags {
+    fn add_dir(dirs: &mut Vec<PathBuf>, dir: PathBuf) {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CompileCommand {
+    directory: String,
+    file: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    command: String,
+}
+
+/// the flags of every unit of a `compile_commands.json`
+#[derive(Default)]
+pub struct CompileDatabase {
+    project_path: PathBuf,
+    units: HashMap<PathBuf, CompileFlags>,
+    /// files no command compiles, headers mostly, search the folders of every unit
+    fallback: CompileFlags,
+}
+
+impl CompileDatabase {
+    /// reads the first `compile_commands.json` of the project or of one of its build folders
+    pub fn load(project_path: &str) -> CompileDatabase {
+        let mut database = CompileDatabase {
+            project_path: PathBuf::from(project_path),
+            ..Default::default()
+        };
+        let commands = BUILD_FOLDERS
+            .iter()
+            .map(|folder| {
+                Path::new(project_path)
+                    .join(folder)
+                    .join("compile_commands.json")
+            })
+            .find(|path| path.is_file())
+            .and_then(|path| match fs::read_to_string(&path) {
+                Ok(text) => serde_json::from_str::<Vec<CompileCommand>>(&text)
+                    .map_err(|e| eprintln!("couldn't read {} : {}", path.display(), e))
+                    .ok(),
+                Err(e) => {
+                    eprintln!("couldn't read {} : {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        for command in commands {
+            let directory = PathBuf::from(&command.directory);
+            let arguments = match command.arguments.is_empty() {
+                true => split_command(&command.command),
+                false => command.arguments,
+            };
+            let flags = parse_arguments(&directory, &arguments);
+            for dir in &flags.quote_dirs {
+                CompileFlags::add_dir(&mut database.fallback.quote_dirs, dir.clone());
+            }
+            for dir in &flags.include_dirs {
+                CompileFlags::add_dir(&mut database.fallback.include_dirs, dir.clone());
+            }
+            let file = directory.join(&command.file);
+            database
+                .units
+                .insert(file.canonicalize().unwrap_or(file), flags);
+        }
+        database
+    }
+
+    /// the flags `file_path` is compiled with, followed by the include paths
+    /// and defines configured for its language
+    pub fn flags_for(&self, file_path: &str) -> CompileFlags {
+        let file = Path::new(file_path);
+        let mut flags = match self
+            .units
+            .get(&file.canonicalize().unwrap_or(file.to_path_buf()))
+        {
+            Some(flags) => flags.clone(),
+            None => self.fallback.clone(),
+        };
+        let (include_paths, defines) = get_include_config(file_path);
+        for dir in include_paths {
+            CompileFlags::add_dir(&mut flags.include_dirs, self.project_path.join(dir));
+        }
+        for define in defines {
+            let (name, value) = parse_define(&define);
+            flags.defines.entry(name).or_insert(value);
+        }
+        flags
+    }
+}
+
+/// the words of a shell command, quotes are removed and `\` escapes the next char
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', q) if q != Some('\'') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => word.push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            _ => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// `-DNAME=value`, `-DNAME` is defined as `1`
+fn parse_define(define: &str) -> (String, Option<String>) {
+    match define.split_once('=') {
+        Some((name, value)) => (name.trim().to_string(), Some(value.to_string())),
+        None => (define.trim().to_string(), Some("1".to_string())),
+    }
+}
+
+/// include folders and defines of one compiler invocation, gcc / clang and msvc flags
+fn parse_arguments(directory: &Path, arguments: &[String]) -> CompileFlags {
+    let mut flags = CompileFlags::default();
+    // `/I` is only a flag for `cl`, for the others it starts an absolute path
+    let msvc = arguments
+        .first()
+        .and_then(|c| Path::new(c).file_stem())
+        .map_or(false, |c| {
+            c.eq_ignore_ascii_case("cl") || c.eq_ignore_ascii_case("clang-cl")
+        });
+    let mut i = 0;
+    while i < arguments.len() {
+        let argument = arguments[i].as_str();
+        // `-I dir` and `-Idir` are the same flag
+        let mut value = |prefix: &str| -> Option<String> {
+            if prefix.starts_with('/') && !msvc {
+                return None;
+            }
+            let rest = argument.strip_prefix(prefix)?;
+            match rest.is_empty() {
+                true => {
+                    i += 1;
+                    arguments.get(i).cloned()
+                }
+                false => Some(rest.to_string()),
+            }
+        };
+        if let Some(dir) = value("-iquote") {
+            CompileFlags::add_dir(&mut flags.quote_dirs, directory.join(dir));
+        } else if let Some(dir) = value("-isystem")
+            .or_else(|| value("-idirafter"))
+            .or_else(|| value("-I"))
+            .or_else(|| value("/I"))
+        {
+            CompileFlags::add_dir(&mut flags.include_dirs, directory.join(dir));
+        } else if let Some(file) = value("-include") {
+            flags.forced_includes.push(directory.join(file));
+        } else if let Some(define) = value("-D").or_else(|| value("/D")) {
+            let (name, value) = parse_define(&define);
+            flags.defines.insert(name, value);
+        } else if let Some(name) = value("-U").or_else(|| value("/U")) {
+            flags.defines.insert(name, None);
+        }
+        i += 1;
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_commands_like_a_shell() {
+        assert_eq!(split_command("g++  -c a.cpp"), vec!["g++", "-c", "a.cpp"]);
+        assert_eq!(
+            split_command(r#"cc "-DNAME=a b" -I'my dir' -DQ=\"x\""#),
+            vec!["cc", "-DNAME=a b", "-Imy dir", "-DQ=\"x\""]
+        );
+        assert_eq!(split_command("cc -DEMPTY=''"), vec!["cc", "-DEMPTY="]);
+    }
+
+    #[test]
+    fn reads_include_folders_and_defines() {
+        let arguments = split_command(
+            "g++ -Iinclude -isystem /usr/x -iquote src -include pch.h -DA -DB=2 -UC a.cpp",
+        );
+        let flags = parse_arguments(Path::new("/p"), &arguments);
+        assert_eq!(
+            flags.include_dirs,
+            vec![PathBuf::from("/p/include"), PathBuf::from("/usr/x")]
+        );
+        assert_eq!(flags.quote_dirs, vec![PathBuf::from("/p/src")]);
+        assert_eq!(flags.forced_includes, vec![PathBuf::from("/p/pch.h")]);
+        assert_eq!(flags.defines["A"], Some("1".to_string()));
+        assert_eq!(flags.defines["B"], Some("2".to_string()));
+        assert_eq!(flags.defines["C"], None);
+    }
+
+    #[test]
+    fn slash_flags_are_only_read_for_msvc() {
+        let msvc = parse_arguments(
+            Path::new("/p"),
+            &split_command("cl.exe /Iinclude /DA a.cpp"),
+        );
+        assert_eq!(msvc.include_dirs, vec![PathBuf::from("/p/include")]);
+        assert!(msvc.defines.contains_key("A"));
+        let gcc = parse_arguments(Path::new("/p"), &split_command("gcc /Iinclude a.c"));
+        assert!(gcc.include_dirs.is_empty());
+    }
+}
@@ -0,0 +1,853 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::{de::DeserializeOwned, de::IgnoredAny, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::data::{get_import_reader, get_language_key};
+
+/// extensions tried for a script import that names none
+const SCRIPT_EXTENSIONS: [&str; 8] = ["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs", "json"];
+
+/// files making their folder a python project, its `src` or the folder itself is a source root
+const PYTHON_PROJECT_FILES: [&str; 3] = ["pyproject.toml", "setup.py", "setup.cfg"];
+
+/// conditions of a `package.json` `exports` entry, in the order they are tried,
+/// the declarations are read before the code
+const EXPORT_CONDITIONS: [&str; 6] = ["types", "import", "module", "require", "node", "default"];
+
+/// the top level modules of the python standard library, separated by spaces
+const PYTHON_STANDARD_MODULES: &str = "\
+    abc aifc antigravity argparse array ast asynchat asyncio asyncore atexit audioop \
+    base64 bdb binascii bisect builtins bz2 cProfile calendar cgi cgitb chunk cmath cmd \
+    code codecs codeop collections colorsys compileall concurrent configparser \
+    contextlib contextvars copy copyreg crypt csv ctypes curses dataclasses datetime dbm \
+    decimal difflib dis distutils doctest email encodings ensurepip enum errno \
+    faulthandler fcntl filecmp fileinput fnmatch fractions ftplib functools gc \
+    genericpath getopt getpass gettext glob graphlib grp gzip hashlib heapq hmac html \
+    http idlelib imaplib imghdr imp importlib inspect io ipaddress itertools json \
+    keyword lib2to3 linecache locale logging lzma mailbox mailcap marshal math mimetypes \
+    mmap modulefinder msilib msvcrt multiprocessing netrc nis nntplib nt ntpath \
+    nturl2path numbers opcode operator optparse os ossaudiodev pathlib pdb pickle \
+    pickletools pipes pkgutil platform plistlib poplib posix posixpath pprint profile \
+    pstats pty pwd py_compile pyclbr pydoc pydoc_data pyexpat queue quopri random re \
+    readline reprlib resource rlcompleter runpy sched secrets select selectors shelve \
+    shlex shutil signal site smtpd smtplib sndhdr socket socketserver spwd sqlite3 \
+    sre_compile sre_constants sre_parse ssl stat statistics string stringprep struct \
+    subprocess sunau symtable sys sysconfig syslog tabnanny tarfile telnetlib tempfile \
+    termios textwrap this threading time timeit tkinter token tokenize tomllib trace \
+    traceback tracemalloc tty turtle turtledemo types typing unicodedata unittest urllib \
+    uu uuid venv warnings wave weakref webbrowser winreg winsound wsgiref xdrlib xml \
+    xmlrpc zipapp zipfile zipimport zlib zoneinfo";
+
+/// the headers of the c standard library, those of c++ have no extension
+const C_STANDARD_HEADERS: &str = "\
+    assert.h complex.h ctype.h errno.h fenv.h float.h inttypes.h iso646.h limits.h \
+    locale.h math.h setjmp.h signal.h stdalign.h stdarg.h stdatomic.h stdbool.h stddef.h \
+    stdint.h stdio.h stdlib.h stdnoreturn.h string.h tgmath.h threads.h time.h uchar.h \
+    wchar.h wctype.h";
+
+/// the modules built into node, also imported as `node:fs`
+const NODE_BUILTIN_MODULES: &str = "\
+    assert async_hooks buffer child_process cluster console crypto dgram dns events fs \
+    http http2 https module net os path perf_hooks process querystring readline stream \
+    string_decoder timers tls tty url util v8 vm worker_threads zlib";
+
+/// a package imported from outside the project, `serde`, `react`, `numpy`...
+#[derive(Debug, Serialize, Clone)]
+pub struct ExternalDependency {
+    /// the crate, the npm package or the top level python module
+    pub name: String,
+    /// where the package comes from, `crates`, `npm`, `pypi`...
+    pub ecosystem: String,
+    /// the version the manifests of the project ask for
+    pub version: Option<String>,
+    /// the file it is entered through, when it is installed in the project
+    pub location: Option<String>,
+    /// the files importing it
+    pub importers: Vec<usize>,
+}
+
+/// `name = "1.0"` or `name = { version = "1.0", path = "..", workspace = true }`
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+enum DeclaredDependency {
+    Version(String),
+    Detailed(DetailedDependency),
+    /// poetry allows a list of constraints, they are not read
+    Other(IgnoredAny),
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+struct DetailedDependency {
+    version: Option<String>,
+    path: Option<String>,
+    git: Option<String>,
+    workspace: bool,
+}
+
+impl DeclaredDependency {
+    /// what is asked for, a folder or a repository when there is no version
+    fn requirement(&self) -> Option<String> {
+        match self {
+            DeclaredDependency::Version(version) => Some(version.clone()),
+            DeclaredDependency::Detailed(d) => d
+                .version
+                .clone()
+                .or_else(|| d.path.as_ref().map(|p| format!("path {}", p)))
+                .or_else(|| d.git.as_ref().map(|g| format!("git {}", g))),
+            DeclaredDependency::Other(_) => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CargoManifest {
+    package: Option<CargoPackage>,
+    lib: Option<CargoLib>,
+    workspace: Option<CargoWorkspace>,
+    dependencies: HashMap<String, DeclaredDependency>,
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: HashMap<String, DeclaredDependency>,
+    #[serde(rename = "build-dependencies")]
+    build_dependencies: HashMap<String, DeclaredDependency>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CargoPackage {
+    name: String,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+struct CargoLib {
+    name: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CargoWorkspace {
+    members: Vec<String>,
+    dependencies: HashMap<String, DeclaredDependency>,
+}
+
+impl CargoManifest {
+    /// the dependency a crate is imported as, `serde_json` is declared `serde-json` too
+    fn dependency(&self, name: &str) -> Option<&DeclaredDependency> {
+        [
+            &self.dependencies,
+            &self.dev_dependencies,
+            &self.build_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        .find(|(key, _)| key.replace('-', "_") == name)
+        .map(|(_, d)| d)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PyProject {
+    project: Option<PyProjectTable>,
+    tool: Option<PyProjectTools>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PyProjectTable {
+    dependencies: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PyProjectTools {
+    poetry: Option<PoetryTable>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PoetryTable {
+    dependencies: HashMap<String, DeclaredDependency>,
+}
+
+/// `compilerOptions` of a `tsconfig.json` or `jsconfig.json`
+#[derive(Default, Clone)]
+struct ScriptConfig {
+    /// folder the non relative imports are also searched in
+    base_url: Option<PathBuf>,
+    /// `(pattern, targets)`, `@app/*` -> `/project/src/app/*`
+    paths: Vec<(String, Vec<String>)>,
+}
+
+/// the packages of the project, what non relative imports are found through
+///
+/// read from the manifests in the folders of the scanned files, `Cargo.toml`,
+/// `package.json`, `tsconfig.json` and the python project files
+#[derive(Default)]
+pub struct PackageRoots {
+    project_path: PathBuf,
+    /// folders absolute python imports start from, the project last
+    python_roots: Vec<PathBuf>,
+    /// folder -> the `(distribution, version)` its requirements ask for
+    python_requirements: HashMap<PathBuf, Vec<(String, Option<String>)>>,
+    /// name -> folder of every `package.json` of the project
+    script_packages: HashMap<String, PathBuf>,
+    /// folder -> its `package.json`
+    script_manifests: HashMap<PathBuf, Value>,
+    /// folder -> its `tsconfig.json` or `jsconfig.json`
+    script_configs: HashMap<PathBuf, ScriptConfig>,
+    /// crate name, `-` written `_` -> its root file
+    crates: HashMap<String, PathBuf>,
+    /// folder -> its `Cargo.toml`
+    cargo_manifests: HashMap<PathBuf, CargoManifest>,
+}
+
+impl PackageRoots {
+    /// reads the manifests of every folder holding a file of the project, up to
+    /// the project, and the crates of the workspaces they declare
+    pub fn load(project_path: &str, all_files: &[&String]) -> PackageRoots {
+        let mut packages = PackageRoots {
+            project_path: PathBuf::from(project_path),
+            ..Default::default()
+        };
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        for file in all_files {
+            let file = Path::new(file.as_str());
+            for folder in packages.folders_above(file) {
+                // the folders above it were read along with it
+                if !seen.insert(folder.clone()) {
+                    break;
+                }
+                packages.read_folder(&folder);
+            }
+            // `a/b/c.py` of the packages `a` and `a.b` is imported from the folder above `a`
+            if file.extension().map_or(false, |e| e == "py") {
+                let mut root = file.parent();
+                let mut in_package = false;
+                while let Some(dir) = root.filter(|d| d.join("__init__.py").is_file()) {
+                    root = dir.parent();
+                    in_package = true;
+                }
+                if let Some(root) = root.filter(|_| in_package) {
+                    packages.add_python_root(root.to_path_buf());
+                }
+            }
+        }
+        packages.read_cargo_members(&mut seen);
+        let project = packages.project_path.clone();
+        packages.add_python_root(project);
+        packages
+    }
+
+    /// the folders above a file, nearest first, up to the project when it is in it
+    fn folders_above(&self, file: &Path) -> Vec<PathBuf> {
+        let in_project = file.starts_with(&self.project_path);
+        file.ancestors()
+            .skip(1)
+            .take_while(|d| !in_project || d.starts_with(&self.project_path))
+            .filter(|d| !d.components().any(|c| c.as_os_str() == "node_modules"))
+            .map(|d| d.to_path_buf())
+            .collect()
+    }
+
+    fn add_python_root(&mut self, root: PathBuf) {
+        if !self.python_roots.contains(&root) {
+            self.python_roots.push(root);
+        }
+    }
+
+    fn read_folder(&mut self, folder: &Path) {
+        if let Some(manifest) = read_toml::<CargoManifest>(&folder.join("Cargo.toml")) {
+            self.add_crate(folder, &manifest);
+            self.cargo_manifests.insert(folder.to_path_buf(), manifest);
+        }
+        if let Some(manifest) = read_json(&folder.join("package.json")) {
+            if let Some(name) = manifest.get("name").and_then(|n| n.as_str()) {
+                self.script_packages
+                    .entry(name.to_string())
+                    .or_insert(folder.to_path_buf());
+            }
+            self.script_manifests.insert(folder.to_path_buf(), manifest);
+        }
+        for config in ["tsconfig.json", "jsconfig.json"] {
+            if let Some(config) = read_script_config(&folder.join(config), 0) {
+                self.script_configs.insert(folder.to_path_buf(), config);
+                break;
+            }
+        }
+        if PYTHON_PROJECT_FILES
+            .iter()
+            .any(|f| folder.join(f).is_file())
+        {
+            let src = folder.join("src");
+            self.add_python_root(match src.is_dir() {
+                true => src,
+                false => folder.to_path_buf(),
+            });
+        }
+        let mut requirements = fs::read_to_string(folder.join("requirements.txt"))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(parse_requirement)
+            .collect::<Vec<(String, Option<String>)>>();
+        if let Some(pyproject) = read_toml::<PyProject>(&folder.join("pyproject.toml")) {
+            let project = pyproject.project.unwrap_or_default();
+            requirements.extend(
+                project
+                    .dependencies
+                    .iter()
+                    .filter_map(|r| parse_requirement(r)),
+            );
+            let poetry = pyproject.tool.and_then(|t| t.poetry).unwrap_or_default();
+            requirements.extend(
+                poetry
+                    .dependencies
+                    .iter()
+                    .map(|(name, d)| (name.clone(), d.requirement())),
+            );
+        }
+        if !requirements.is_empty() {
+            self.python_requirements
+                .insert(folder.to_path_buf(), requirements);
+        }
+    }
+
+    /// the crate a manifest declares, `use zoo_core::Dog` enters its `lib.rs`
+    fn add_crate(&mut self, folder: &Path, manifest: &CargoManifest) {
+        let package = match &manifest.package {
+            Some(package) => package,
+            None => return,
+        };
+        let lib = manifest.lib.clone().unwrap_or_default();
+        let name = lib.name.unwrap_or(package.name.clone()).replace('-', "_");
+        let root = match lib.path {
+            Some(path) => folder.join(path),
+            None => [folder.join("src/lib.rs"), folder.join("src/main.rs")]
+                .into_iter()
+                .find(|f| f.is_file())
+                .unwrap_or(folder.join("src/lib.rs")),
+        };
+        self.crates.entry(name).or_insert(root);
+    }
+
+    /// the members of the workspaces and the path dependencies whose folders
+    /// hold no scanned file, `crates/*` takes every folder of `crates`
+    fn read_cargo_members(&mut self, seen: &mut HashSet<PathBuf>) {
+        let mut pending = self
+            .cargo_manifests
+            .keys()
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        while let Some(folder) = pending.pop() {
+            let manifest = match self.cargo_manifests.get(&folder) {
+                Some(manifest) => manifest,
+                None => continue,
+            };
+            let members = manifest.workspace.iter().flat_map(|w| &w.members);
+            let mut folders = members
+                .flat_map(|member| match member.strip_suffix("/*") {
+                    Some(parent) => fs::read_dir(folder.join(parent))
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .collect::<Vec<PathBuf>>(),
+                    None => vec![folder.join(member)],
+                })
+                .collect::<Vec<PathBuf>>();
+            folders.extend(
+                [&manifest.dependencies, &manifest.dev_dependencies]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(_, d)| match d {
+                        DeclaredDependency::Detailed(d) => d.path.as_ref(),
+                        _ => None,
+                    })
+                    .map(|path| folder.join(path)),
+            );
+            for member in folders {
+                let member = member.canonicalize().unwrap_or(member);
+                if !seen.insert(member.clone()) {
+                    continue;
+                }
+                if let Some(manifest) = read_toml::<CargoManifest>(&member.join("Cargo.toml")) {
+                    self.add_crate(&member, &manifest);
+                    self.cargo_manifests.insert(member.clone(), manifest);
+                    pending.push(member);
+                }
+            }
+        }
+    }
+
+    /// the root file of a crate of the project, by the name it is imported as
+    pub fn crate_root(&self, name: &str) -> Option<&PathBuf> {
+        self.crates.get(name)
+    }
+
+    pub fn python_roots(&self) -> &Vec<PathBuf> {
+        &self.python_roots
+    }
+
+    /// the file a non relative script import is aliased to by the closest
+    /// `tsconfig.json`, through its `paths`, then its `baseUrl`
+    pub fn script_alias(&self, file_path: &str, import: &str) -> Option<PathBuf> {
+        let config = Path::new(file_path)
+            .ancestors()
+            .skip(1)
+            .find_map(|d| self.script_configs.get(d))?;
+        // the pattern with the longest start before its `*` wins
+        let mut matches = config
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let star = import.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                    Some((prefix.len(), star, targets))
+                }
+                None => (pattern == import).then_some((usize::MAX, "", targets)),
+            })
+            .collect::<Vec<(usize, &str, &Vec<String>)>>();
+        matches.sort_by_key(|(prefix, _, _)| std::cmp::Reverse(*prefix));
+        matches
+            .into_iter()
+            .flat_map(|(_, star, targets)| targets.iter().map(move |t| t.replace('*', star)))
+            .map(PathBuf::from)
+            .chain(config.base_url.iter().map(|base| base.join(import)))
+            .find_map(|target| script_file(&target))
+    }
+
+    /// the entry of a package of the project an import names, `@zoo/core/util`
+    /// is the `./util` export of the `package.json` named `@zoo/core`
+    ///
+    /// an entry that is not built yet is looked for in the `src` of the package
+    pub fn script_package(&self, import: &str) -> Option<PathBuf> {
+        let (name, subpath) = split_package_specifier(import);
+        let folder = self.script_packages.get(&name)?;
+        package_entry(folder, &subpath).or_else(|| {
+            let source = match subpath.as_str() {
+                "." => "index",
+                subpath => subpath.trim_start_matches("./"),
+            };
+            script_file(&folder.join("src").join(source))
+        })
+    }
+
+    /// fills the version the manifests above `importer` ask for, and for an
+    /// npm package the file its `node_modules` folder enters it through
+    pub fn describe(&self, dependency: &mut ExternalDependency, importer: &str) {
+        let folders = self.folders_above(Path::new(importer));
+        match dependency.ecosystem.as_str() {
+            "crates" => {
+                // `serde = { workspace = true }` is versioned by the workspace above
+                let mut from_workspace = false;
+                for manifest in folders.iter().filter_map(|f| self.cargo_manifests.get(f)) {
+                    let declared = match from_workspace {
+                        true => manifest
+                            .workspace
+                            .as_ref()
+                            .and_then(|w| {
+                                w.dependencies
+                                    .iter()
+                                    .find(|(key, _)| key.replace('-', "_") == dependency.name)
+                            })
+                            .map(|(_, d)| d),
+                        false => manifest.dependency(&dependency.name),
+                    };
+                    match declared {
+                        Some(DeclaredDependency::Detailed(d)) if d.workspace => {
+                            from_workspace = true
+                        }
+                        Some(declared) => {
+                            dependency.version = declared.requirement();
+                            break;
+                        }
+                        None => {}
+                    }
+                }
+            }
+            "npm" => {
+                dependency.version = folders
+                    .iter()
+                    .filter_map(|f| self.script_manifests.get(f))
+                    .find_map(|manifest| {
+                        [
+                            "dependencies",
+                            "devDependencies",
+                            "peerDependencies",
+                            "optionalDependencies",
+                        ]
+                        .iter()
+                        .find_map(|table| manifest.get(table)?.get(&dependency.name)?.as_str())
+                    })
+                    .map(|v| v.to_string());
+                let installed = Path::new(importer)
+                    .ancestors()
+                    .skip(1)
+                    .map(|d| d.join("node_modules").join(&dependency.name))
+                    .find(|d| d.join("package.json").is_file());
+                if let Some(folder) = installed {
+                    if dependency.version.is_none() {
+                        dependency.version = read_json(&folder.join("package.json"))
+                            .and_then(|m| Some(m.get("version")?.as_str()?.to_string()));
+                    }
+                    dependency.location = package_entry(&folder, ".")
+                        .map(|entry| entry.to_string_lossy().to_string());
+                }
+            }
+            "pypi" => {
+                // `yaml` is often distributed as `PyYAML`, only the same names are matched
+                let normalized = |name: &str| name.to_lowercase().replace(&['-', '.'][..], "_");
+                dependency.version = folders
+                    .iter()
+                    .filter_map(|f| self.python_requirements.get(f))
+                    .flatten()
+                    .find(|(name, _)| normalized(name) == normalized(&dependency.name))
+                    .and_then(|(_, version)| version.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// where the packages a file imports come from
+pub fn ecosystem(file_path: &str) -> String {
+    let key = get_import_reader(file_path)
+        .0
+        .or_else(|| get_language_key(file_path));
+    match key.as_deref() {
+        Some("rs") => "crates",
+        Some("py") => "pypi",
+        Some("js" | "ts") => "npm",
+        Some("java" | "kt") => "maven",
+        Some("cs") => "nuget",
+        Some("go") => "go",
+        Some("c" | "cpp") => "system",
+        Some(key) => key,
+        None => "",
+    }
+    .to_string()
+}
+
+/// the standard library of the language of the file, `std` of rust, `os` of
+/// python, `<vector>` of c++, it is not a dependency of the project
+pub fn is_standard_library(file_path: &str, name: &str) -> bool {
+    let key = get_import_reader(file_path)
+        .0
+        .or_else(|| get_language_key(file_path));
+    let first = |separator| name.split(separator).next().unwrap_or(name);
+    match key.as_deref() {
+        Some("rs") => ["std", "core", "alloc", "proc_macro", "test"].contains(&name),
+        Some("py") => {
+            name.starts_with('_') || PYTHON_STANDARD_MODULES.split(' ').any(|m| m == name)
+        }
+        Some("c" | "cpp") => {
+            C_STANDARD_HEADERS.split(' ').any(|h| h == name)
+                || name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+        }
+        // `net/http`, the path of any other module starts with a domain, `github.com/..`
+        Some("go") => !first('/').contains('.'),
+        Some("java" | "kt") => ["java", "javax", "kotlin"].contains(&first('.')),
+        Some("cs") => first('.') == "System",
+        Some("js" | "ts") => {
+            name.starts_with("node:") || NODE_BUILTIN_MODULES.split(' ').any(|m| m == name)
+        }
+        _ => false,
+    }
+}
+
+/// the file a script import names, as written, with one of the script
+/// extensions or as the `index` of a folder
+pub fn script_file(base: &Path) -> Option<PathBuf> {
+    // `./graph.utils` keeps its dots, the extension is added after them
+    let with_extension = |path: &Path, e: &str| PathBuf::from(format!("{}.{}", path.display(), e));
+    [base.to_path_buf()]
+        .into_iter()
+        .chain(SCRIPT_EXTENSIONS.iter().flat_map(|e| {
+            [
+                with_extension(base, e),
+                with_extension(&base.join("index"), e),
+            ]
+        }))
+        .find(|candidate| candidate.is_file())
+}
+
+/// `@zoo/core/util` -> (`@zoo/core`, `./util`), `react` -> (`react`, `.`)
+pub fn split_package_specifier(import: &str) -> (String, String) {
+    let parts = match import.starts_with('@') {
+        true => 2,
+        false => 1,
+    };
+    let mut segments = import.splitn(parts + 1, '/');
+    let name = segments
+        .by_ref()
+        .take(parts)
+        .collect::<Vec<&str>>()
+        .join("/");
+    match segments.next() {
+        Some(subpath) => (name, format!("./{}", subpath)),
+        None => (name, ".".to_string()),
+    }
+}
+
+/// the file a subpath of the package in `folder` is, through the `exports`
+/// of its `package.json`, or its `types`, `module` or `main` without them
+fn package_entry(folder: &Path, subpath: &str) -> Option<PathBuf> {
+    let manifest = read_json(&folder.join("package.json")).unwrap_or(Value::Null);
+    let target = match manifest.get("exports") {
+        // a package with `exports` has no other entries
+        Some(exports) => export_target(exports, subpath)?,
+        None if subpath == "." => ["types", "typings", "module", "main"]
+            .iter()
+            .find_map(|field| manifest.get(field)?.as_str())
+            .unwrap_or("index")
+            .to_string(),
+        None => subpath.to_string(),
+    };
+    script_file(&folder.join(target.trim_start_matches("./")))
+}
+
+/// the target of a subpath in `exports`, `./features/*` patterns match with
+/// the longest start before their `*`
+fn export_target(exports: &Value, subpath: &str) -> Option<String> {
+    let subpaths = match exports {
+        Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => map,
+        // `"exports": "./index.js"` or the conditions of `.` alone
+        _ => return condition_target(exports).filter(|_| subpath == "."),
+    };
+    if let Some(target) = subpaths.get(subpath) {
+        return condition_target(target);
+    }
+    subpaths
+        .iter()
+        .filter_map(|(key, target)| {
+            let (prefix, suffix) = key.split_once('*')?;
+            let star = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), star, target))
+        })
+        .max_by_key(|(prefix, _, _)| *prefix)
+        .and_then(|(_, star, target)| Some(condition_target(target)?.replace('*', star)))
+}
+
+/// a target of `exports`, a path, the first of a list that is one or the
+/// first known condition of an object, a `null` target is excluded
+fn condition_target(target: &Value) -> Option<String> {
+    match target {
+        Value::String(path) => Some(path.clone()),
+        Value::Array(targets) => targets.iter().find_map(condition_target),
+        Value::Object(conditions) => EXPORT_CONDITIONS
+            .iter()
+            .find_map(|c| condition_target(conditions.get(*c)?)),
+        _ => None,
+    }
+}
+
+/// `baseUrl` and `paths` of a script config, the ones a relative `extends`
+/// gives are kept unless it sets its own
+fn read_script_config(path: &Path, depth: usize) -> Option<ScriptConfig> {
+    let config = read_json(path)?;
+    let folder = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut base = config
+        .get("extends")
+        .and_then(|e| e.as_str())
+        .filter(|e| e.starts_with('.') && depth < 8)
+        .and_then(|e| {
+            let extended = folder.join(e);
+            match extended.extension() {
+                Some(_) => read_script_config(&extended, depth + 1),
+                None => read_script_config(&extended.with_extension("json"), depth + 1),
+            }
+        })
+        .unwrap_or_default();
+    let options = match config.get("compilerOptions") {
+        Some(options) => options,
+        None => return Some(base),
+    };
+    let base_url = options
+        .get("baseUrl")
+        .and_then(|b| b.as_str())
+        .map(|b| folder.join(b));
+    if let Some(paths) = options.get("paths").and_then(|p| p.as_object()) {
+        // the targets start from `baseUrl`, or from the config without one
+        let from = base_url.clone().unwrap_or(folder.to_path_buf());
+        base.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets.as_array().into_iter().flatten();
+                (
+                    pattern.clone(),
+                    targets
+                        .filter_map(|t| t.as_str())
+                        .map(|t| from.join(t).to_string_lossy().to_string())
+                        .collect(),
+                )
+            })
+            .collect();
+    }
+    if base_url.is_some() {
+        base.base_url = base_url;
+    }
+    Some(base)
+}
+
+/// `requests[socks] >= 2.0 ; python_version > "3"` -> (`requests`, `>= 2.0`)
+fn parse_requirement(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.split(&['#', ';'][..]).next()?.trim();
+    // `-r other.txt` and the other options
+    if line.starts_with('-') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| !(c.is_alphanumeric() || "-_.".contains(c)))
+        .unwrap_or(line.len());
+    if end == 0 {
+        return None;
+    }
+    let version = line[end..].trim();
+    let version = match version.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, v)| v).trim(),
+        None => version,
+    };
+    Some((
+        line[..end].to_string(),
+        Some(version.to_string()).filter(|v| !v.is_empty()),
+    ))
+}
+
+fn read_toml<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let text = fs::read_to_string(path).ok()?;
+    toml::from_str(&text)
+        .map_err(|e| eprintln!("couldn't read {} : {}", path.display(), e))
+        .ok()
+}
+
+/// a json file, comments and trailing commas are allowed as `tsconfig.json` does
+fn read_json(path: &Path) -> Option<Value> {
+    let text = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text)
+        .or_else(|_| serde_json::from_str(&strip_json_comments(&text)))
+        .map_err(|e| eprintln!("couldn't read {} : {}", path.display(), e))
+        .ok()
+}
+
+fn strip_json_comments(text: &str) -> String {
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().map_or(false, |c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+    Regex::new(r",(\s*[}\]])")
+        .unwrap()
+        .replace_all(&json, "$1")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn splits_package_specifiers() {
+        assert_eq!(
+            split_package_specifier("@zoo/core/util"),
+            ("@zoo/core".to_string(), "./util".to_string())
+        );
+        assert_eq!(
+            split_package_specifier("lodash/fp/map"),
+            ("lodash".to_string(), "./fp/map".to_string())
+        );
+        assert_eq!(
+            split_package_specifier("react"),
+            ("react".to_string(), ".".to_string())
+        );
+    }
+
+    #[test]
+    fn finds_export_targets() {
+        let exports = json!({
+            ".": { "types": "./index.d.ts", "default": "./index.js" },
+            "./features/*": "./src/features/*.js",
+            "./features/private/*": null,
+        });
+        assert_eq!(
+            export_target(&exports, "."),
+            Some("./index.d.ts".to_string())
+        );
+        assert_eq!(
+            export_target(&exports, "./features/zoo"),
+            Some("./src/features/zoo.js".to_string())
+        );
+        assert_eq!(export_target(&exports, "./features/private/zoo"), None);
+        assert_eq!(export_target(&exports, "./other"), None);
+        assert_eq!(
+            export_target(&json!("./main.js"), "."),
+            Some("./main.js".to_string())
+        );
+        assert_eq!(export_target(&json!("./main.js"), "./util"), None);
+    }
+
+    #[test]
+    fn parses_requirements() {
+        assert_eq!(
+            parse_requirement("requests[socks] >= 2.0 ; python_version > \"3\""),
+            Some(("requests".to_string(), Some(">= 2.0".to_string())))
+        );
+        assert_eq!(
+            parse_requirement("numpy==1.26 # pinned"),
+            Some(("numpy".to_string(), Some("==1.26".to_string())))
+        );
+        assert_eq!(
+            parse_requirement("flask"),
+            Some(("flask".to_string(), None))
+        );
+        assert_eq!(parse_requirement("-r other.txt"), None);
+        assert_eq!(parse_requirement("# a comment"), None);
+    }
+
+    #[test]
+    fn strips_json_comments() {
+        let text = "{\n  // the base\n  \"baseUrl\": \"./src\", /* kept */\n  \"url\": \"http://a//b\",\n}";
+        let json: Value = serde_json::from_str(&strip_json_comments(text)).unwrap();
+        assert_eq!(json, json!({ "baseUrl": "./src", "url": "http://a//b" }));
+    }
+}
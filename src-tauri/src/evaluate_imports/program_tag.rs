@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-use crate::data::get_data_types;
+use crate::{
+    data::get_data_types,
+    qualified_names::parent_path,
+    span::Span,
+    tag_entry::{Access, ClassKind},
+    type_ref::{base_name, generic_args},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ClassType {
     Undiscovered(String),
     Connected(usize, usize),
     DataType(usize),
+    /// a template parameter of the tag or its class, it is never looked up
+    TemplateParam(String),
 }
 
 impl ClassType {
@@ -16,6 +24,17 @@ impl ClassType {
         n
     }
 
+    /// `std::vector<Animal>` -> `std::vector` and the types of its arguments, `[Animal]`
+    pub fn with_generic_args(path: &str, type_ref: &str) -> (ClassType, Vec<ClassType>) {
+        (
+            ClassType::new(path, base_name(type_ref)),
+            generic_args(type_ref)
+                .into_iter()
+                .map(|a| ClassType::new(path, a))
+                .collect(),
+        )
+    }
+
     pub fn needed_class(&self) -> Option<&String> {
         if let ClassType::Undiscovered(name) = self {
             return Some(name);
@@ -38,63 +57,257 @@ impl ClassType {
     pub fn set_class(&mut self, file: usize, t: usize) {
         *self = ClassType::Connected(file, t);
     }
+
+    pub fn set_as_template_param(&mut self, params: &[String]) {
+        if let ClassType::Undiscovered(name) = self {
+            if params.contains(name) {
+                *self = ClassType::TemplateParam(name.clone());
+            }
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ProgramTag {
+    /// also structs, unions, enums, namespaces and typedefs, the parents of a
+    /// typedef are the aliased type
     Class {
         name: String,
+        qualified_name: String,
+        kind: ClassKind,
         parents: Vec<ClassType>,
+        /// access every parent is inherited with, in the order of `parents`
+        parents_access: Vec<Access>,
+        /// `T` of `template<typename T> class Box`
+        template_params: Vec<String>,
+        /// types named in the template arguments of the parents, the
+        /// contains / generic-arg relation of `class Zoo : Pen<Animal>`
+        generic_args: Vec<ClassType>,
+        span: Span,
     },
     /// for class the representation is (file_number, tag_number)
-    Function { name: String, class: ClassType },
+    Function {
+        name: String,
+        qualified_name: String,
+        class: ClassType,
+        /// none for a function that is not a member
+        access: Option<Access>,
+        template_params: Vec<String>,
+        /// types named in the template arguments of the return type
+        generic_args: Vec<ClassType>,
+        span: Span,
+        /// `(Context, *Dog) (error)`, the parameter and result types of a go function
+        signature: Option<String>,
+        /// `func (r *Repo) Save()` of go, not a method of a `Repo` value
+        pointer_receiver: bool,
+    },
     /// for class the representation is (file_number, tag_number)
-    Object { name: String, class: ClassType },
+    Object {
+        name: String,
+        qualified_name: String,
+        class: ClassType,
+        /// none for a variable that is not a member
+        access: Option<Access>,
+        /// types named in the template arguments of the type, `Animal` of
+        /// `std::vector<Animal> zoo`, the contains / generic-arg relation
+        generic_args: Vec<ClassType>,
+        span: Span,
+    },
 }
 
 impl ProgramTag {
     pub fn get_name(&self) -> &String {
         match self {
-            ProgramTag::Class { name, parents: _ } => name,
-            ProgramTag::Function { name, class: _ } => name,
-            ProgramTag::Object { name, class: _ } => name,
+            ProgramTag::Class { name, .. } => name,
+            ProgramTag::Function { name, .. } => name,
+            ProgramTag::Object { name, .. } => name,
+        }
+    }
+
+    /// `ns::Outer::Inner`, the identity of the tag across the project
+    pub fn get_qualified_name(&self) -> &String {
+        match self {
+            ProgramTag::Class { qualified_name, .. } => qualified_name,
+            ProgramTag::Function { qualified_name, .. } => qualified_name,
+            ProgramTag::Object { qualified_name, .. } => qualified_name,
+        }
+    }
+
+    /// the scope the types the tag needs are looked up from, `ns::Outer` for `ns::Outer::Inner`
+    pub fn get_lookup_context(&self) -> &str {
+        parent_path(self.get_qualified_name())
+    }
+
+    /// where the tag is defined in its file
+    pub fn get_span(&self) -> &Span {
+        match self {
+            ProgramTag::Class { span, .. } => span,
+            ProgramTag::Function { span, .. } => span,
+            ProgramTag::Object { span, .. } => span,
+        }
+    }
+
+    /// access of a member, none for a class or a tag that is not a member
+    pub fn get_access(&self) -> Option<Access> {
+        match self {
+            ProgramTag::Class { .. } => None,
+            ProgramTag::Function { access, .. } => *access,
+            ProgramTag::Object { access, .. } => *access,
+        }
+    }
+
+    /// part of the api of its class, everything that is not a private or protected member
+    pub fn is_public(&self) -> bool {
+        match self.get_access() {
+            Some(Access::Private) | Some(Access::Protected) => false,
+            _ => true,
+        }
+    }
+
+    /// template parameters of the tag itself, a member also sees those of its class
+    pub fn get_template_params(&self) -> &[String] {
+        match self {
+            ProgramTag::Class {
+                template_params, ..
+            } => template_params,
+            ProgramTag::Function {
+                template_params, ..
+            } => template_params,
+            ProgramTag::Object { .. } => &[],
+        }
+    }
+
+    /// the classes the tag needs that are template parameters in its scope are never looked up
+    pub fn mark_template_params(&mut self, params: &[String]) {
+        match self {
+            ProgramTag::Class {
+                parents,
+                generic_args,
+                ..
+            } => parents
+                .iter_mut()
+                .chain(generic_args.iter_mut())
+                .for_each(|c| c.set_as_template_param(params)),
+            ProgramTag::Function {
+                class,
+                generic_args,
+                ..
+            }
+            | ProgramTag::Object {
+                class,
+                generic_args,
+                ..
+            } => std::iter::once(class)
+                .chain(generic_args.iter_mut())
+                .for_each(|c| c.set_as_template_param(params)),
         }
     }
 
     pub fn is_class(&self) -> bool {
-        if let ProgramTag::Class {
-            name: _,
-            parents: _,
-        } = self
-        {
+        if let ProgramTag::Class { .. } = self {
             return true;
         }
         return false;
     }
 
+    /// a rust `impl` block or a later part of a c# `partial class`, its members
+    /// belong to the type it names
+    pub fn is_implementation(&self) -> bool {
+        matches!(
+            self,
+            ProgramTag::Class {
+                kind: ClassKind::Implementation,
+                ..
+            }
+        )
+    }
+
+    /// adds the parents of another class, `impl Display for Dog` makes `Display` a parent of `Dog`,
+    /// a parent it already has is not added again
+    pub fn extend_parents(&mut self, other: &ProgramTag) {
+        if let (
+            ProgramTag::Class {
+                parents,
+                parents_access,
+                generic_args,
+                ..
+            },
+            ProgramTag::Class {
+                parents: other_parents,
+                parents_access: other_access,
+                generic_args: other_args,
+                ..
+            },
+        ) = (self, other)
+        {
+            for (parent, access) in other_parents.iter().zip(other_access) {
+                let name = parent.needed_class();
+                if name.is_some() && parents.iter().any(|p| p.needed_class() == name) {
+                    continue;
+                }
+                parents.push(parent.clone());
+                parents_access.push(*access);
+            }
+            generic_args.extend(other_args.iter().cloned());
+        }
+    }
+
+    /// the parents or the class, followed by the generic args
     pub fn needed_class(&self) -> Vec<Option<&String>> {
         match self {
-            ProgramTag::Class { name: _, parents } => {
-                parents.iter().map(|p| p.needed_class()).collect()
+            ProgramTag::Class {
+                parents,
+                generic_args,
+                ..
+            } => parents
+                .iter()
+                .chain(generic_args.iter())
+                .map(|p| p.needed_class())
+                .collect(),
+            ProgramTag::Function {
+                class,
+                generic_args,
+                ..
             }
-            ProgramTag::Function { name: _, class } => vec![class.needed_class()],
-            ProgramTag::Object { name: _, class } => vec![class.needed_class()],
+            | ProgramTag::Object {
+                class,
+                generic_args,
+                ..
+            } => std::iter::once(class)
+                .chain(generic_args.iter())
+                .map(|c| c.needed_class())
+                .collect(),
         }
     }
 
+    /// `(class_i, file, tag)`, `class_i` indexes what `needed_class` returned
     pub fn put_class_data(&mut self, file_tag_i: Vec<(usize, usize, usize)>) {
-        match self {
-            ProgramTag::Class { name: _, parents } => {
-                file_tag_i.iter().for_each(|(class_i, file, tag)| {
-                    parents.get_mut(*class_i).unwrap().set_class(*file, *tag);
-                });
-            }
-            ProgramTag::Function { name: _, class } => {
-                class.set_class(file_tag_i[0].1, file_tag_i[0].2)
+        let mut needed = match self {
+            ProgramTag::Class {
+                parents,
+                generic_args,
+                ..
+            } => parents
+                .iter_mut()
+                .chain(generic_args.iter_mut())
+                .collect::<Vec<&mut ClassType>>(),
+            ProgramTag::Function {
+                class,
+                generic_args,
+                ..
             }
-            ProgramTag::Object { name: _, class } => {
-                class.set_class(file_tag_i[0].1, file_tag_i[0].2)
+            | ProgramTag::Object {
+                class,
+                generic_args,
+                ..
+            } => std::iter::once(class)
+                .chain(generic_args.iter_mut())
+                .collect::<Vec<&mut ClassType>>(),
+        };
+        file_tag_i.iter().for_each(|(class_i, file, tag)| {
+            if let Some(c) = needed.get_mut(*class_i) {
+                c.set_class(*file, *tag);
             }
-        }
+        });
     }
 }
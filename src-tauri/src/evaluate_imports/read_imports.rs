@@ -1,45 +1,202 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::compile_commands::{CompileDatabase, CompileFlags};
+use super::packages::{script_file, split_package_specifier, PackageRoots};
+use crate::code_lexer::SourceFiles;
+use crate::data::get_import_reader;
+use crate::type_ref::split_top_level;
 
 pub enum Import {
     ///     if a file is imported
     File(String),
-    ///     if a module is imported
+    ///     if a module or a package of the project is imported, the file
+    ///     standing for it, its `__init__.py`, `lib.rs` or `package.json` entry
     Module(String),
-    ///     if a pre built package is imported
+    ///     if a pre built package is imported, the name it is known by
     Package(String),
+    ///     if a file of the package of the file itself is seen, nothing is
+    ///     imported, its classes are visible without an import
+    SamePackage(String),
+}
+
+/// one import statement, what it reads and the names it takes from there
+pub struct ImportStatement {
+    /// the module, file or package, written the way the language writes it
+    pub path: String,
+    /// `(name, local name)`, `import { Graph as G } from './graph'` takes
+    /// `(Graph, G)`, empty when the module itself or all of it is imported
+    pub names: Vec<(String, String)>,
 }
 
-pub fn get_imported_files(project_path: &String, file_path: &str) -> Result<Vec<Import>, String> {
+impl ImportStatement {
+    fn module(path: &str) -> ImportStatement {
+        ImportStatement {
+            path: path.to_string(),
+            names: Vec::new(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref RUST_IMPORT: Regex = Regex::new(
+        r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?\s+)?(?:(use)\s+([^;]+);|mod\s+(\w+)\s*;|extern\s+crate\s+(\w+))",
+    )
+    .unwrap();
+    static ref PYTHON_IMPORT: Regex = Regex::new(
+        r"(?m)^[ \t]*(?:from[ \t]+(\.*[\w.]*)[ \t]+import[ \t]*(\([^)]*\)|(?:[^\n\\#]|\\\n)*)|import[ \t]+((?:[^\n\\#]|\\\n)+))",
+    )
+    .unwrap();
+    static ref SCRIPT_FROM_IMPORT: Regex = Regex::new(
+        r#"\b(?:import|export)\s+(?:type\s+)?([\w\s{},*$]*?)\s*\bfrom\s*['"]([^'"\n]+)['"]"#,
+    )
+    .unwrap();
+    static ref SCRIPT_BARE_IMPORT: Regex =
+        Regex::new(r#"\bimport\s*(?:\(\s*)?['"]([^'"\n]+)['"]"#).unwrap();
+    static ref SCRIPT_REQUIRE: Regex = Regex::new(
+        r#"(?:\b(?:const|let|var)\s+(\{[^}]*\}|[\w$]+)\s*=\s*)?\brequire\s*\(\s*['"]([^'"\n]+)['"]\s*\)"#,
+    )
+    .unwrap();
+    static ref JVM_IMPORT: Regex = Regex::new(
+        r"(?m)^[ \t]*import\s+(?:static\s+)?(\w+(?:\.\w+)*(?:\.\*)?)(?:\s+as\s+(\w+))?",
+    )
+    .unwrap();
+    static ref CSHARP_USING: Regex = Regex::new(
+        r"(?m)^[ \t]*(?:global\s+)?using\s+(?:static\s+)?(?:(\w+)\s*=\s*)?([\w.]+)\s*;",
+    )
+    .unwrap();
+    static ref GO_IMPORT: Regex = Regex::new(r"(?m)^[ \t]*import\b\s*(\([^)]*\)|[^\n]*)").unwrap();
+    static ref GO_IMPORT_PATH: Regex = Regex::new(r#"(?m)^[ \t]*(?:[\w.]+\s+)?"([^"]+)""#).unwrap();
+    static ref PHP_INCLUDE: Regex =
+        Regex::new(r#"\b(?:include|require)(?:_once)?\s*\(?\s*['"]([^'"\n]+)['"]"#).unwrap();
+}
+
+/// the imports of a file with the names each of them takes
+pub fn get_imported_files(
+    project_path: &String,
+    file_path: &str,
+    sources: &SourceFiles,
+    namespaces: &HashMap<String, Vec<String>>,
+    compile_database: &CompileDatabase,
+    packages: &PackageRoots,
+) -> Result<Vec<(Import, Vec<(String, String)>)>, String> {
     // Determine the file extension
     let path = Path::new(file_path);
-    let extension = match path.extension() {
-        Some(ext) => ext.to_str().unwrap_or(""),
-        None => return Err("Could not determine file extension.".to_string()),
-    };
+    if path.extension().is_none() {
+        return Err("Could not determine file extension.".to_string());
+    }
 
-    // Read the file content
-    let content = match std::fs::read_to_string(file_path) {
-        Ok(content) => content,
-        Err(_) => return Err("Could not read the file.".to_string()),
+    // an import written in a comment or a string is not one
+    let (content, code) = match sources.get(file_path) {
+        Some(source) => (&source.text, &source.code),
+        None => return Err("Could not read the file.".to_string()),
     };
 
-    // Match the file extension to a programming language and extract imports
-    let imported_files = match extension {
-        "rs" => extract_imports_rust(&content),
-        "py" => extract_imports_python(&content),
-        "js" | "ts" => extract_imports_js_ts(&content),
-        "java" => extract_imports_java(&content),
-        "c" | "cpp" | "h" => extract_imports_c_cpp(&content),
-        "php" => extract_imports_php(&content),
+    // Match the language of the file to the way its imports are written
+    let (reader, import_patterns) = get_import_reader(file_path);
+    let reader = reader.as_deref();
+    let compile_flags = match reader {
+        Some("c" | "cpp") => compile_database.flags_for(file_path),
+        _ => CompileFlags::default(),
+    };
+    let imported_files = match reader {
+        Some("rs") => extract_imports_rust(&content, &code),
+        Some("py") => extract_imports_python(&content, &code),
+        Some("js" | "ts") => extract_imports_js_ts(&content, &code),
+        Some("java" | "kt") => extract_imports_java(&content, &code),
+        Some("cs") => extract_imports_csharp(&content, &code),
+        Some("go") => extract_imports_go(&content, &code),
+        Some("c" | "cpp") => extract_imports_c_cpp(&content, &compile_flags),
+        Some("php") => extract_imports_php(&content),
+        None if !import_patterns.is_empty() => extract_imports_patterns(&content, &import_patterns),
         _ => return Err("Unsupported or unknown file type.".to_string()),
-    }
-    .into_iter()
-    .map(|f| resolve_import_paths(project_path, file_path, &f))
-    .collect::<Vec<Import>>();
+    };
+    let go_module = match reader {
+        Some("go") => go_module(file_path),
+        _ => None,
+    };
+    let source_roots = match reader {
+        Some("java" | "kt") => jvm_source_roots(file_path, &content),
+        _ => Vec::new(),
+    };
+    let mut imported_files = imported_files
+        .into_iter()
+        .flat_map(|statement| {
+            let f = &statement.path;
+            let imports = match reader {
+                Some("rs") => vec![resolve_rust_path(file_path, packages, f)],
+                Some("py") => resolve_python_import(file_path, packages, &statement),
+                Some("js" | "ts") => {
+                    vec![resolve_script_import(project_path, file_path, packages, f)]
+                }
+                Some("java" | "kt") => resolve_jvm_import(file_path, &source_roots, f),
+                Some("cs") => resolve_csharp_using(file_path, namespaces, f),
+                Some("go") => resolve_go_import(file_path, &go_module, f),
+                Some("c" | "cpp") => vec![resolve_c_include(
+                    project_path,
+                    file_path,
+                    &compile_flags,
+                    f,
+                )],
+                _ => vec![resolve_import_paths(project_path, file_path, f)],
+            };
+            imports
+                .into_iter()
+                .map(move |import| (import, statement.names.clone()))
+        })
+        .collect::<Vec<(Import, Vec<(String, String)>)>>();
+
+    // the files of its own package are seen, not imported
+    let own_package = match reader {
+        Some("java" | "kt") => jvm_package(&content)
+            .map(|package| resolve_jvm_import(file_path, &source_roots, &format!("{}.*", package))),
+        Some("go") => Some(resolve_go_import(file_path, &go_module, ".")),
+        _ => None,
+    };
+    imported_files.extend(
+        own_package
+            .into_iter()
+            .flatten()
+            .filter_map(|import| match import {
+                Import::File(path) => Some((Import::SamePackage(path), Vec::new())),
+                _ => None,
+            }),
+    );
 
     Ok(imported_files)
 }
 
+/// the matches of `regex` whose first char is code, not a comment or a string
+fn code_captures<'t>(regex: &Regex, content: &'t str, code: &str) -> Vec<regex::Captures<'t>> {
+    regex
+        .captures_iter(content)
+        .filter(|caps| {
+            let start = caps.get(0).map_or(0, |m| m.start());
+            // the match may start on the white space before the keyword
+            let keyword = start + content[start..].len() - content[start..].trim_start().len();
+            code.get(keyword..keyword + 1) == content.get(keyword..keyword + 1)
+        })
+        .collect()
+}
+
+/// `a as b`, `a: b` and `a` of an import list, `(name, local name)`
+fn imported_name(item: &str, alias_separator: &str) -> Option<(String, String)> {
+    let item = item.trim();
+    let item = item.strip_prefix("type ").unwrap_or(item).trim();
+    if item.is_empty() || item == "*" {
+        return None;
+    }
+    Some(match item.split_once(alias_separator) {
+        Some((name, local)) => (name.trim().to_string(), local.trim().to_string()),
+        None => (item.to_string(), item.to_string()),
+    })
+}
+
 fn resolve_import_paths(project_path: &String, file_path: &str, import: &String) -> Import {
     // Get the base file's parent directory
     let file_relative_path = Path::new(file_path)
@@ -63,94 +220,1060 @@ fn resolve_import_paths(project_path: &String, file_path: &str, import: &String)
 }
 
 fn format_path(path: PathBuf) -> Import {
+    Import::File(normalize_path(path))
+}
+
+/// the file standing for an imported module or package of the project
+fn format_module_path(path: PathBuf) -> Import {
+    Import::Module(normalize_path(path))
+}
+
+fn normalize_path(path: PathBuf) -> String {
     let path_str = path
         .canonicalize()
         .unwrap_or(path.to_path_buf())
         .to_string_lossy()
         .to_string();
 
-    Import::File(
-        path_str
-            .strip_prefix("\\\\?\\")
-            .unwrap_or(&path_str)
-            .to_string(),
-    )
+    path_str
+        .strip_prefix("\\\\?\\")
+        .unwrap_or(&path_str)
+        .to_string()
 }
 
-// Function to extract imports for Rust
-fn extract_imports_rust(content: &str) -> Vec<String> {
+/// `use` trees over several lines, `mod data;` and `extern crate`, `pub use`
+/// re-exports the same way, each item of a tree is a statement of its own
+fn extract_imports_rust(content: &str, code: &str) -> Vec<ImportStatement> {
     let mut imports = Vec::new();
-    for line in content.lines() {
-        if line.trim().starts_with("mod ") || line.trim().starts_with("extern crate ") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.trim_end_matches(';').to_string());
+    for caps in code_captures(&RUST_IMPORT, content, code) {
+        if let Some(tree) = caps.get(2) {
+            // a comment inside the tree is not part of it
+            let tree = tree
+                .as_str()
+                .lines()
+                .map(|line| line.split("//").next().unwrap_or(""))
+                .collect::<Vec<&str>>()
+                .join(" ");
+            for (path, local) in expand_use_tree(&tree) {
+                imports.push(match path.rsplit_once("::") {
+                    Some((module, "*")) => ImportStatement::module(module),
+                    Some((_, name)) if local != "self" => ImportStatement {
+                        path: path.clone(),
+                        names: vec![(name.to_string(), local)],
+                    },
+                    _ => ImportStatement::module(&path),
+                });
             }
+        } else if let Some(module) = caps.get(3) {
+            // `mod tests {` is declared inline, `mod data;` is a file of the module
+            imports.push(ImportStatement::module(&format!(
+                "self::{}",
+                module.as_str()
+            )));
+        } else if let Some(krate) = caps.get(4) {
+            imports.push(ImportStatement::module(krate.as_str()));
         }
     }
     imports
 }
 
-// Function to extract imports for Python
-fn extract_imports_python(content: &str) -> Vec<String> {
+/// `a::{b::C, d as E}` -> `(a::b::C, C)`, `(a::d, E)`, the path and its local name
+fn expand_use_tree(tree: &str) -> Vec<(String, String)> {
+    let tree = tree.trim();
+    match tree.find('{') {
+        Some(open) if tree.ends_with('}') => {
+            let prefix = &tree[..open];
+            split_top_level(&tree[open + 1..tree.len() - 1], ',')
+                .into_iter()
+                .filter(|item| !item.trim().is_empty())
+                .flat_map(|item| expand_use_tree(item))
+                .map(|(item, local)| match item.as_str() {
+                    // `a::{self, B}` imports `a` itself
+                    "self" => (prefix.trim_end_matches("::").to_string(), local),
+                    _ => (format!("{}{}", prefix, item), local),
+                })
+                .collect()
+        }
+        _ => {
+            let (path, local) = match tree.split_once(" as ") {
+                Some((path, local)) => (path.trim(), local.trim()),
+                None => (tree, tree.rsplit("::").next().unwrap_or(tree)),
+            };
+            vec![(path.to_string(), local.to_string())]
+        }
+    }
+}
+
+/// resolves a rust `use` or `mod` path through the module tree, the longest
+/// start of the path that is a module file wins
+///
+/// `crate::` starts at the crate root, `self::` and a plain path at the module
+/// of the file and `super::` at its parent, a plain path that is no module is
+/// a crate of the workspace or a package
+fn resolve_rust_path(file_path: &str, packages: &PackageRoots, import: &str) -> Import {
+    let file = Path::new(file_path);
+    let mut segments = import
+        .split("::")
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>();
+    let mut base = rust_module_dir(file);
+    let from_package = match segments.first() {
+        Some(&"crate") => {
+            segments.remove(0);
+            base = match rust_crate_root(file) {
+                Some(root) => root,
+                None => return Import::Module(import.to_string()),
+            };
+            false
+        }
+        Some(&"self") => {
+            segments.remove(0);
+            false
+        }
+        Some(&"super") => {
+            while segments.first() == Some(&"super") {
+                segments.remove(0);
+                base = base.parent().map(|p| p.to_path_buf()).unwrap_or(base);
+            }
+            false
+        }
+        _ => true,
+    };
+
+    let find_module = |base: &Path, segments: &[&str]| {
+        (1..=segments.len()).rev().find_map(|n| {
+            let module = base.join(segments[..n].join("/"));
+            [module.with_extension("rs"), module.join("mod.rs")]
+                .into_iter()
+                .find(|candidate| candidate.is_file())
+        })
+    };
+    if let Some(module) = find_module(&base, &segments) {
+        return format_path(module);
+    }
+    if from_package {
+        let name = segments.first().unwrap_or(&import);
+        // `use zoo_core::Dog` of another crate of the workspace
+        return match packages.crate_root(name) {
+            Some(root) => {
+                let crate_dir = root.parent().unwrap_or_else(|| Path::new(""));
+                match find_module(crate_dir, &segments[1..]) {
+                    Some(module) => format_path(module),
+                    None => format_module_path(root.clone()),
+                }
+            }
+            None => Import::Package(name.to_string()),
+        };
+    }
+    // `use crate::Config` names an item of the module file itself
+    match rust_module_file(&base) {
+        Some(module_file) if module_file != file => format_path(module_file),
+        _ => Import::Module(import.to_string()),
+    }
+}
+
+/// directory holding the submodules of a file, `src/data/mod.rs` and
+/// `src/data.rs` are both the module `src/data`
+fn rust_module_dir(file: &Path) -> PathBuf {
+    let dir = file.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("main" | "lib" | "mod") | None => dir,
+        Some(stem) => dir.join(stem),
+    }
+}
+
+/// file defining the module of a directory
+fn rust_module_file(dir: &Path) -> Option<PathBuf> {
+    [
+        dir.join("mod.rs"),
+        dir.join("lib.rs"),
+        dir.join("main.rs"),
+        dir.with_extension("rs"),
+    ]
+    .into_iter()
+    .find(|f| f.is_file())
+}
+
+/// `src` next to the closest `Cargo.toml`, or the closest directory holding a
+/// `lib.rs` or `main.rs`
+fn rust_crate_root(file: &Path) -> Option<PathBuf> {
+    let dirs = file.ancestors().skip(1).collect::<Vec<&Path>>();
+    dirs.iter()
+        .find(|d| d.join("Cargo.toml").is_file() && d.join("src").is_dir())
+        .map(|d| d.join("src"))
+        .or_else(|| {
+            dirs.iter()
+                .find(|d| d.join("lib.rs").is_file() || d.join("main.rs").is_file())
+                .map(|d| d.to_path_buf())
+        })
+}
+
+/// `import a.b as c, d` and `from ..pkg import (x,\n y as z)`, a relative
+/// module keeps its leading dots
+fn extract_imports_python(content: &str, code: &str) -> Vec<ImportStatement> {
     let mut imports = Vec::new();
-    for line in content.lines() {
-        if line.trim().starts_with("import ") || line.trim().starts_with("from ") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.to_string());
+    for caps in code_captures(&PYTHON_IMPORT, content, code) {
+        // a list over several lines may hold comments and `\` line ends
+        let list = |m: regex::Match| {
+            m.as_str()
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or(""))
+                .collect::<Vec<&str>>()
+                .join(" ")
+                .replace('\\', " ")
+        };
+        if let (Some(module), Some(names)) = (caps.get(1), caps.get(2)) {
+            let names = list(names);
+            imports.push(ImportStatement {
+                path: module.as_str().to_string(),
+                names: split_top_level(names.trim().trim_matches(&['(', ')'][..]), ',')
+                    .into_iter()
+                    .filter_map(|item| imported_name(item, " as "))
+                    .collect(),
+            });
+        } else if let Some(modules) = caps.get(3) {
+            for module in list(modules).split(',') {
+                let module = module.split(" as ").next().unwrap_or("").trim();
+                if !module.is_empty() {
+                    imports.push(ImportStatement::module(module));
+                }
             }
         }
     }
     imports
 }
 
-// Function to extract imports for JavaScript/TypeScript
-fn extract_imports_js_ts(content: &str) -> Vec<String> {
-    let mut imports = Vec::new();
-    for line in content.lines() {
-        if line.trim().starts_with("import ") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.trim_matches(&['"', '\'', '{', '}'][..]).to_string());
+/// the file of a python module, `a.b` is `a/b.py` or the package
+/// `a/b/__init__.py`, next to the file or under a source root, `..a` starts one
+/// folder above the package of the file, a module found nowhere is the package
+/// of its first name
+///
+/// the names of a `from` import that are modules of their own are imported
+/// as files too
+fn resolve_python_import(
+    file_path: &str,
+    packages: &PackageRoots,
+    statement: &ImportStatement,
+) -> Vec<Import> {
+    let dots = statement.path.len() - statement.path.trim_start_matches('.').len();
+    let module = statement.path[dots..].replace('.', "/");
+    let file = Path::new(file_path);
+    let folders = match dots {
+        0 => file
+            .parent()
+            .into_iter()
+            .map(|f| f.to_path_buf())
+            .chain(packages.python_roots().iter().cloned())
+            .collect::<Vec<PathBuf>>(),
+        _ => file
+            .ancestors()
+            .nth(dots)
+            .into_iter()
+            .map(|f| f.to_path_buf())
+            .collect(),
+    };
+    let find = |module: &str| -> Option<Import> {
+        folders.iter().find_map(|folder| {
+            let path = folder.join(module);
+            let package = path.join("__init__.py");
+            match module.is_empty() {
+                false if path.with_extension("py").is_file() => {
+                    Some(format_path(path.with_extension("py")))
+                }
+                // `from . import a` in the `__init__.py` itself
+                _ if package.is_file() && package != file => Some(format_module_path(package)),
+                // a namespace package, a folder without `__init__.py`
+                false if path.is_dir() => Some(format_module_path(path)),
+                _ => None,
             }
+        })
+    };
+    let mut imports = Vec::new();
+    match find(&module) {
+        Some(import) => imports.push(import),
+        // `from . import a` of a folder without `__init__.py`
+        None if module.is_empty() => {}
+        None if dots == 0 => imports.push(Import::Package(
+            module.split('/').next().unwrap_or(&module).to_string(),
+        )),
+        None => imports.push(Import::Module(statement.path.clone())),
+    }
+    for (name, _) in &statement.names {
+        let submodule = match module.is_empty() {
+            true => name.clone(),
+            false => format!("{}/{}", module, name),
+        };
+        if let Some(import) = find(&submodule) {
+            imports.push(import);
         }
     }
     imports
 }
 
-// Function to extract imports for Java
-fn extract_imports_java(content: &str) -> Vec<String> {
+/// `import a, { b as c } from`, `import * as ns from`, `export { d } from`,
+/// `export * from`, `import 'x'`, `import('x')` and `require('x')`, the lists
+/// may run over several lines
+fn extract_imports_js_ts(content: &str, code: &str) -> Vec<ImportStatement> {
+    let list_names = |list: &str, alias_separator: &str| {
+        split_top_level(list.trim().trim_matches(&['{', '}'][..]), ',')
+            .into_iter()
+            .filter_map(|item| imported_name(item, alias_separator))
+            .collect::<Vec<(String, String)>>()
+    };
+
     let mut imports = Vec::new();
-    for line in content.lines() {
-        if line.trim().starts_with("import ") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.trim_end_matches(';').to_string());
-            }
+    for caps in code_captures(&SCRIPT_FROM_IMPORT, content, code) {
+        let clause = caps[1].trim();
+        let mut names = Vec::new();
+        // `Default, { a }` or `Default, * as ns`, the default export comes first
+        let (default, rest) = match clause.starts_with(&['{', '*'][..]) {
+            true => ("", clause),
+            false => clause.split_once(',').unwrap_or((clause, "")),
+        };
+        if !default.trim().is_empty() {
+            names.push(("default".to_string(), default.trim().to_string()));
+        }
+        if let Some(open) = rest.find('{') {
+            names.extend(list_names(&rest[open..], " as "));
         }
+        imports.push(ImportStatement {
+            path: caps[2].to_string(),
+            names,
+        });
+    }
+    for caps in code_captures(&SCRIPT_BARE_IMPORT, content, code) {
+        imports.push(ImportStatement::module(&caps[1]));
+    }
+    for caps in code_captures(&SCRIPT_REQUIRE, content, code) {
+        imports.push(ImportStatement {
+            path: caps[2].to_string(),
+            // `const { a, b: c } = require('x')`, a plain `const x =` is the module
+            names: match caps.get(1) {
+                Some(list) if list.as_str().starts_with('{') => list_names(list.as_str(), ":"),
+                _ => Vec::new(),
+            },
+        });
     }
     imports
 }
 
-// Function to extract includes for C/C++
-fn extract_imports_c_cpp(content: &str) -> Vec<String> {
+/// a relative import is a file next to the importing one, its extension and
+/// an `index` file are tried when it names none
+///
+/// a non relative one is an alias of the closest `tsconfig.json`, a package of
+/// the project or a package of `node_modules`
+fn resolve_script_import(
+    project_path: &String,
+    file_path: &str,
+    packages: &PackageRoots,
+    import: &str,
+) -> Import {
+    if !import.starts_with('.') && !import.starts_with('/') {
+        if let Some(file) = packages.script_alias(file_path, import) {
+            return format_path(file);
+        }
+        if let Some(entry) = packages.script_package(import) {
+            return format_module_path(entry);
+        }
+        return Import::Package(split_package_specifier(import).0);
+    }
+    let folder = Path::new(file_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    match script_file(&folder.join(import)) {
+        Some(file) => format_path(file),
+        None => resolve_import_paths(project_path, file_path, &import.to_string()),
+    }
+}
+
+/// `import a.B`, `import a.*`, `import static a.B.c` and kotlin `import a.B as C`
+///
+/// `import static a.B.c` is found in `a.B`
+fn extract_imports_java(content: &str, code: &str) -> Vec<ImportStatement> {
     let mut imports = Vec::new();
+    for caps in code_captures(&JVM_IMPORT, content, code) {
+        if let Some(import) = caps.get(1) {
+            let import = import.as_str();
+            imports.push(match import.rsplit_once('.') {
+                Some((_, "*")) | None => ImportStatement::module(import),
+                Some((_, name)) => ImportStatement {
+                    path: import.to_string(),
+                    names: vec![(
+                        name.to_string(),
+                        caps.get(2).map_or(name, |local| local.as_str()).to_string(),
+                    )],
+                },
+            });
+        }
+    }
+    imports
+}
+
+/// `a.b` of `package a.b;`, the classes of the package are seen without an import
+fn jvm_package(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("package "))
+        .map(|package| package.trim().trim_end_matches(';').trim())
+        .filter(|package| !package.is_empty())
+}
+
+/// the directory a java or kotlin file declares its `package` from, and the
+/// other roots of its module it sees, `src/main/java` sees `src/main/kotlin`,
+/// `src/test/java` sees every source set
+///
+/// none when the folders of the file do not follow its package, kotlin allows it
+fn jvm_source_roots(file_path: &str, content: &str) -> Vec<PathBuf> {
+    let package = jvm_package(content).unwrap_or("");
+    let mut root = Path::new(file_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
+    for folder in package.split('.').filter(|f| !f.is_empty()).rev() {
+        if root.file_name().map_or(true, |name| name != folder) {
+            return Vec::new();
+        }
+        root.pop();
+    }
+
+    let mut roots = vec![root.clone()];
+    let own_set = match root.parent() {
+        Some(set) => set.to_path_buf(),
+        None => return roots,
+    };
+    let is_test = own_set.file_name().map_or(false, |name| {
+        name.to_string_lossy().to_lowercase().contains("test")
+    });
+    let src = own_set
+        .parent()
+        .filter(|src| src.file_name().map_or(false, |name| name == "src"));
+    if let Some(src) = src {
+        for source_set in std::fs::read_dir(src).into_iter().flatten().flatten() {
+            if !is_test && source_set.path() != own_set {
+                continue;
+            }
+            for language in std::fs::read_dir(source_set.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                let path = language.path();
+                if path.is_dir() && path != root {
+                    roots.push(path);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// resolves a java or kotlin import through the source roots, `com.zoo.Dog` is
+/// `com/zoo/Dog.java` or `.kt` and `com.zoo.*` every file of `com/zoo`
+///
+/// a package is spread over the roots, `src/main/java` and `src/test/java` hold
+/// parts of it, the longest start of the path that is a file wins, so the
+/// nested class of `com.zoo.Dog.Tail` and the member of
+/// `import static com.zoo.Dog.bark` are found in `com/zoo/Dog.java`, an import
+/// found in no root is of the package before its first class, `org.junit`
+fn resolve_jvm_import(file_path: &str, source_roots: &Vec<PathBuf>, import: &str) -> Vec<Import> {
+    let (path, wildcard) = match import.strip_suffix(".*") {
+        Some(path) => (path, true),
+        None => (import, false),
+    };
+    let segments = path.split('.').collect::<Vec<&str>>();
+    let under = |root: &PathBuf, end: usize| -> PathBuf {
+        segments[..end]
+            .iter()
+            .fold(root.clone(), |dir, s| dir.join(s))
+    };
+
+    let package_dirs = source_roots
+        .iter()
+        .map(|root| under(root, segments.len()))
+        .filter(|dir| wildcard && dir.is_dir())
+        .collect::<Vec<PathBuf>>();
+    if !package_dirs.is_empty() {
+        return package_dirs
+            .iter()
+            .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+            .map(|entry| entry.path())
+            .filter(|file| {
+                file.is_file()
+                    && file != Path::new(file_path)
+                    && matches!(
+                        file.extension().and_then(|e| e.to_str()),
+                        Some("java" | "kt" | "kts")
+                    )
+            })
+            .map(format_path)
+            .collect();
+    }
+    for root in source_roots {
+        for end in (1..=segments.len()).rev() {
+            for extension in ["java", "kt"] {
+                let file = under(root, end).with_extension(extension);
+                if file.is_file() {
+                    return vec![format_path(file)];
+                }
+            }
+        }
+    }
+    let package = segments
+        .iter()
+        .take_while(|s| !s.starts_with(char::is_uppercase))
+        .copied()
+        .collect::<Vec<&str>>();
+    match package.is_empty() {
+        true => vec![Import::Package(path.to_string())],
+        false => vec![Import::Package(package.join("."))],
+    }
+}
+
+/// the namespace a c# line declares, `namespace Zoo.Animals {` or `namespace Zoo.Animals;`
+fn csharp_namespace(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("namespace ")?.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
+
+/// namespace -> the c# files declaring it, a file without a namespace is in
+/// the global namespace `""`
+///
+/// c# namespaces do not follow the folders, so the files are found by what
+/// they declare
+pub fn csharp_namespaces(
+    all_files: &Vec<&String>,
+    sources: &SourceFiles,
+) -> HashMap<String, Vec<String>> {
+    let mut namespaces: HashMap<String, Vec<String>> = HashMap::new();
+    for file in all_files {
+        if Path::new(file.as_str())
+            .extension()
+            .map_or(true, |e| e != "cs")
+        {
+            continue;
+        }
+        let content = sources.get(*file).map_or("", |source| source.text.as_str());
+        let mut declared = content.lines().filter_map(csharp_namespace).peekable();
+        if declared.peek().is_none() {
+            namespaces
+                .entry(String::new())
+                .or_default()
+                .push(file.to_string());
+        }
+        for namespace in declared {
+            let files = namespaces.entry(namespace.to_string()).or_default();
+            if !files.contains(file) {
+                files.push(file.to_string());
+            }
+        }
+    }
+    namespaces
+}
+
+/// `using Zoo.Animals;`, `global using static Zoo.Util;` and `using Pet = Zoo.Dog;`
+///
+/// a file also sees the namespaces it is declared in, their parents and the
+/// global namespace `""`, `namespace Zoo.Animals` sees `Zoo.Animals` and `Zoo`
+fn extract_imports_csharp(content: &str, code: &str) -> Vec<ImportStatement> {
+    let mut imports = vec![ImportStatement::module("")];
+    for caps in code_captures(&CSHARP_USING, content, code) {
+        let import = &caps[2];
+        imports.push(match caps.get(1) {
+            // `using Pet = Zoo.Dog;` takes `Dog` as `Pet`
+            Some(alias) => ImportStatement {
+                path: import.to_string(),
+                names: vec![(
+                    import.rsplit('.').next().unwrap_or(import).to_string(),
+                    alias.as_str().to_string(),
+                )],
+            },
+            None => ImportStatement::module(import),
+        });
+    }
     for line in content.lines() {
-        if line.trim().starts_with("#include ") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.trim_matches(&['"', '<', '>'][..]).to_string());
+        if let Some(namespace) = csharp_namespace(line) {
+            let mut namespace = namespace;
+            loop {
+                imports.push(ImportStatement::module(namespace));
+                match namespace.rsplit_once('.') {
+                    Some((parent, _)) => namespace = parent,
+                    None => break,
+                }
             }
         }
     }
     imports
 }
 
-// Function to extract includes for PHP
-fn extract_imports_php(content: &str) -> Vec<String> {
+/// the files declaring the namespace of a `using`, for the type of
+/// `using static Zoo.Util;` or `using Pet = Zoo.Dog;` the files of `Zoo`,
+/// a namespace declared in no file is a package
+fn resolve_csharp_using(
+    file_path: &str,
+    namespaces: &HashMap<String, Vec<String>>,
+    import: &str,
+) -> Vec<Import> {
+    let mut namespace = import;
+    loop {
+        if let Some(files) = namespaces.get(namespace) {
+            return files
+                .iter()
+                .filter(|file| file.as_str() != file_path)
+                .map(|file| Import::File(file.clone()))
+                .collect();
+        }
+        match namespace.rsplit_once('.') {
+            Some((parent, _)) => namespace = parent,
+            None => break,
+        }
+    }
+    match import.is_empty() {
+        true => Vec::new(),
+        false => vec![Import::Package(import.to_string())],
+    }
+}
+
+/// the paths of `import "fmt"` and of an `import ( .. )` block, aliased or not
+fn extract_imports_go(content: &str, code: &str) -> Vec<ImportStatement> {
     let mut imports = Vec::new();
+    for caps in code_captures(&GO_IMPORT, content, code) {
+        let specs = caps[1].trim_start_matches('(').replace(';', "\n");
+        for spec in GO_IMPORT_PATH.captures_iter(&specs) {
+            imports.push(ImportStatement::module(&spec[1]));
+        }
+    }
+    imports
+}
+
+/// the module path the `go.mod` above a go file declares and its folder
+fn go_module(file_path: &str) -> Option<(String, PathBuf)> {
+    let mut dir = Path::new(file_path).parent();
+    while let Some(d) = dir {
+        if let Ok(content) = std::fs::read_to_string(d.join("go.mod")) {
+            let module = content
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))?
+                .trim()
+                .trim_matches('"');
+            return Some((module.to_string(), d.to_path_buf()));
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// the go files of the package folder an import path names
+///
+/// `github.com/zoo/app/store` is the folder `store` of the module
+/// `github.com/zoo/app`, a relative path is taken from the folder of the file,
+/// the tests of another package are not part of it, an import out of the
+/// module is a package
+fn resolve_go_import(
+    file_path: &str,
+    go_module: &Option<(String, PathBuf)>,
+    import: &str,
+) -> Vec<Import> {
+    let own_folder = Path::new(file_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let folder = match (import.starts_with('.'), go_module) {
+        (true, _) => own_folder.join(import),
+        (false, Some((module, root))) if import == module => root.clone(),
+        (false, Some((module, root))) => match import.strip_prefix(&format!("{}/", module)) {
+            Some(package) => root.join(package),
+            None => return vec![Import::Package(import.to_string())],
+        },
+        (false, None) => return vec![Import::Package(import.to_string())],
+    };
+    let is_own_package = folder == own_folder;
+    std::fs::read_dir(&folder)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|file| {
+            let name = file
+                .file_name()
+                .map_or(String::new(), |n| n.to_string_lossy().to_string());
+            file.is_file()
+                && file != Path::new(file_path)
+                && name.ends_with(".go")
+                && (is_own_package || !name.ends_with("_test.go"))
+        })
+        .map(format_path)
+        .collect()
+}
+
+/// the `#include`s of a c / c++ file, `"name"` and `<name>` keep their delimiters
+///
+/// the ones inside an `#if` branch the defines rule out are skipped, a branch
+/// whose condition can not be told is read, forced includes come first
+fn extract_imports_c_cpp(content: &str, flags: &CompileFlags) -> Vec<ImportStatement> {
+    let mut imports = flags
+        .forced_includes
+        .iter()
+        .map(|f| ImportStatement::module(&format!("\"{}\"", f.display())))
+        .collect::<Vec<ImportStatement>>();
+    let mut defines = flags.defines.clone();
+    // for each open `#if`, the state of its current branch and whether an earlier one was taken
+    let mut branches: Vec<(Option<bool>, Option<bool>)> = Vec::new();
     for line in content.lines() {
-        if line.trim().starts_with("include") || line.trim().starts_with("require") {
-            if let Some(import) = line.split_whitespace().nth(1) {
-                imports.push(import.trim_matches(&['"', '\'', ';'][..]).to_string());
+        let directive = match line.trim_start().strip_prefix('#') {
+            Some(directive) => directive.trim_start(),
+            None => continue,
+        };
+        let (name, rest) = directive
+            .split_once(|c: char| !c.is_alphanumeric() && c != '_')
+            .map_or((directive, ""), |(name, _)| {
+                (name, directive[name.len()..].trim())
+            });
+        let active = branches.iter().all(|b| b.0 != Some(false));
+        let sure = branches.iter().all(|b| b.0 == Some(true));
+        match name {
+            "if" | "ifdef" | "ifndef" => {
+                let condition = match name {
+                    "ifdef" => defined(&defines, rest),
+                    "ifndef" => defined(&defines, rest).map(|d| !d),
+                    _ => eval_condition(rest, &defines),
+                };
+                branches.push((condition, condition));
+            }
+            "elif" | "else" => {
+                if let Some((current, taken)) = branches.last_mut() {
+                    let condition = match name {
+                        "elif" => eval_condition(rest, &defines),
+                        _ => Some(true),
+                    };
+                    *current = match *taken {
+                        Some(true) => Some(false),
+                        Some(false) => condition,
+                        // an earlier branch may have been taken
+                        None => condition.filter(|c| !c),
+                    };
+                    *taken = match (*taken, *current) {
+                        (Some(true), _) | (_, Some(true)) => Some(true),
+                        (Some(false), Some(false)) => Some(false),
+                        _ => None,
+                    };
+                }
+            }
+            "endif" => {
+                branches.pop();
             }
+            // a define in a branch that may not be followed is not known anymore
+            "define" if active => {
+                let (macro_name, value) = rest
+                    .split_once(char::is_whitespace)
+                    .map_or((rest, ""), |(n, v)| (n, v.trim()));
+                match sure && !macro_name.contains('(') {
+                    true => defines.insert(macro_name.to_string(), Some(value.to_string())),
+                    false => defines.remove(macro_name.split('(').next().unwrap_or(macro_name)),
+                };
+            }
+            "undef" if active => {
+                match sure {
+                    true => defines.insert(rest.to_string(), None),
+                    false => defines.remove(rest),
+                };
+            }
+            "include" if active => {
+                let end = match rest.chars().next() {
+                    Some('"') => rest[1..].find('"'),
+                    Some('<') => rest[1..].find('>'),
+                    _ => None,
+                };
+                if let Some(end) = end {
+                    imports.push(ImportStatement::module(&rest[..end + 2]));
+                }
+            }
+            _ => {}
         }
     }
     imports
 }
+
+/// is `name` defined, `None` when neither the flags nor the file tell
+fn defined(defines: &HashMap<String, Option<String>>, name: &str) -> Option<bool> {
+    let name = name.split_whitespace().next().unwrap_or("");
+    defines.get(name).map(|value| value.is_some())
+}
+
+/// value of an `#if` condition, `None` when it depends on what is not known
+///
+/// `defined`, `!`, `&&`, `||`, parentheses, numbers and macros are understood
+fn eval_condition(condition: &str, defines: &HashMap<String, Option<String>>) -> Option<bool> {
+    let condition = condition.split("//").next().unwrap_or("").trim();
+    let any_or = split_top_level(condition, '|');
+    if any_or.len() > 1 {
+        let parts = any_or.iter().filter(|p| !p.trim().is_empty());
+        return parts.fold(Some(false), |res, p| {
+            match (res, eval_condition(p, defines)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            }
+        });
+    }
+    let all_and = split_top_level(condition, '&');
+    if all_and.len() > 1 {
+        let parts = all_and.iter().filter(|p| !p.trim().is_empty());
+        return parts.fold(Some(true), |res, p| {
+            match (res, eval_condition(p, defines)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            }
+        });
+    }
+    if let Some(negated) = condition.strip_prefix('!') {
+        return eval_condition(negated, defines).map(|v| !v);
+    }
+    if let Some(inner) = condition
+        .strip_prefix('(')
+        .and_then(|c| c.strip_suffix(')'))
+    {
+        return eval_condition(inner, defines);
+    }
+    if let Some(name) = condition.strip_prefix("defined") {
+        return defined(defines, name.trim().trim_matches(&['(', ')'][..]));
+    }
+    if let Ok(number) = condition
+        .trim_end_matches(&['L', 'l', 'U', 'u'][..])
+        .parse::<i64>()
+    {
+        return Some(number != 0);
+    }
+    let is_name = condition.chars().all(|c| c.is_alphanumeric() || c == '_');
+    match defines.get(condition) {
+        // an undefined name is `0`
+        Some(None) if is_name => Some(false),
+        Some(Some(value)) if is_name && value != condition => eval_condition(value, defines),
+        _ => None,
+    }
+}
+
+/// `#include "name"` is searched next to the file, then in the quote folders,
+/// `#include <name>` only in the include folders, the project is tried last
+fn resolve_c_include(
+    project_path: &String,
+    file_path: &str,
+    flags: &CompileFlags,
+    include: &str,
+) -> Import {
+    let name = &include[1..include.len() - 1];
+    let own_folder = Path::new(file_path).parent().map(|p| p.to_path_buf());
+    let folders = match include.starts_with('"') {
+        true => own_folder
+            .iter()
+            .chain(flags.quote_dirs.iter())
+            .chain(flags.include_dirs.iter())
+            .collect::<Vec<&PathBuf>>(),
+        false => flags.include_dirs.iter().collect(),
+    };
+    match folders
+        .into_iter()
+        .map(|f| f.join(name))
+        .find(|f| f.is_file())
+    {
+        Some(found) => format_path(found),
+        None => resolve_import_paths(project_path, file_path, &name.to_string()),
+    }
+}
+
+/// imports of a language loaded from a spec, the first group of each match of
+/// its patterns, and the `, ` separated `names` group when it has one
+fn extract_imports_patterns(content: &str, patterns: &[Regex]) -> Vec<ImportStatement> {
+    patterns
+        .iter()
+        .flat_map(|re| {
+            re.captures_iter(content)
+                .filter_map(|caps| {
+                    Some(ImportStatement {
+                        path: caps.get(1)?.as_str().to_string(),
+                        names: caps.name("names").map_or(Vec::new(), |names| {
+                            split_top_level(names.as_str(), ',')
+                                .into_iter()
+                                .filter_map(|item| imported_name(item, " as "))
+                                .collect()
+                        }),
+                    })
+                })
+                .collect::<Vec<ImportStatement>>()
+        })
+        .collect()
+}
+
+/// `include 'a.php';`, `require_once("b.php")` and the other forms of both
+fn extract_imports_php(content: &str) -> Vec<ImportStatement> {
+    PHP_INCLUDE
+        .captures_iter(content)
+        .map(|caps| ImportStatement::module(&caps[1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defines(pairs: &[(&str, Option<&str>)]) -> HashMap<String, Option<String>> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.map(|v| v.to_string())))
+            .collect()
+    }
+
+    /// `(path, [(name, local name)])` of each statement
+    fn read(imports: Vec<ImportStatement>) -> Vec<(String, Vec<(String, String)>)> {
+        imports.into_iter().map(|i| (i.path, i.names)).collect()
+    }
+
+    fn statement(path: &str, names: &[(&str, &str)]) -> (String, Vec<(String, String)>) {
+        let names = names
+            .iter()
+            .map(|(name, local)| (name.to_string(), local.to_string()))
+            .collect();
+        (path.to_string(), names)
+    }
+
+    #[test]
+    fn expands_use_trees() {
+        let owned = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(path, local)| (path.to_string(), local.to_string()))
+                .collect::<Vec<(String, String)>>()
+        };
+        assert_eq!(
+            expand_use_tree("a::{b::C, d as E}"),
+            owned(&[("a::b::C", "C"), ("a::d", "E")])
+        );
+        assert_eq!(
+            expand_use_tree("a::{self, b::{c, D}}"),
+            owned(&[("a", "self"), ("a::b::c", "c"), ("a::b::D", "D")])
+        );
+        assert_eq!(expand_use_tree("std::io::*"), owned(&[("std::io::*", "*")]));
+    }
+
+    #[test]
+    fn reads_rust_imports() {
+        let content = "use crate::data::{Graph, mod_a::*};\nmod tests {\n}\nmod util;\nextern crate serde;\n// use crate::old;\n";
+        let code = content.replace("// use crate::old;", "                 ");
+        assert_eq!(
+            read(extract_imports_rust(content, &code)),
+            vec![
+                statement("crate::data::Graph", &[("Graph", "Graph")]),
+                statement("crate::data::mod_a", &[]),
+                statement("self::util", &[]),
+                statement("serde", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_python_imports() {
+        let content = "import a.b as c, d\nfrom ..pkg import (x,\n    y as z)\n";
+        assert_eq!(
+            read(extract_imports_python(content, content)),
+            vec![
+                statement("a.b", &[]),
+                statement("d", &[]),
+                statement("..pkg", &[("x", "x"), ("y", "z")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_script_imports() {
+        let content = "import Graph, { Node as N } from './graph';\nimport './style.css';\nconst { a, b: c } = require('lib');\n";
+        assert_eq!(
+            read(extract_imports_js_ts(content, content)),
+            vec![
+                statement("./graph", &[("default", "Graph"), ("Node", "N")]),
+                statement("./style.css", &[]),
+                statement("lib", &[("a", "a"), ("b", "c")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_jvm_csharp_and_go_imports() {
+        let java = "import a.B;\nimport a.*;\nimport b.C as D\n";
+        assert_eq!(
+            read(extract_imports_java(java, java)),
+            vec![
+                statement("a.B", &[("B", "B")]),
+                statement("a.*", &[]),
+                statement("b.C", &[("C", "D")]),
+            ]
+        );
+        let csharp = "using Zoo.Util;\nusing Pet = Zoo.Dog;\nnamespace Zoo.Animals {\n";
+        assert_eq!(
+            read(extract_imports_csharp(csharp, csharp)),
+            vec![
+                statement("", &[]),
+                statement("Zoo.Util", &[]),
+                statement("Zoo.Dog", &[("Dog", "Pet")]),
+                statement("Zoo.Animals", &[]),
+                statement("Zoo", &[]),
+            ]
+        );
+        let go = "import \"fmt\"\nimport (\n\tm \"zoo/models\"\n\t\"os\"\n)\n";
+        assert_eq!(
+            read(extract_imports_go(go, go)),
+            vec![
+                statement("fmt", &[]),
+                statement("zoo/models", &[]),
+                statement("os", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_imports_with_spec_patterns() {
+        let patterns = [
+            Regex::new(r#"(?m)^load "([^"]+)"$"#).unwrap(),
+            Regex::new(r"(?m)^from (\S+) take (?P<names>.+)$").unwrap(),
+        ];
+        let content = "load \"lib/a\"\nfrom lib/b take X, Y as Z\n";
+        assert_eq!(
+            read(extract_imports_patterns(content, &patterns)),
+            vec![
+                statement("lib/a", &[]),
+                statement("lib/b", &[("X", "X"), ("Y", "Z")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluates_known_conditions() {
+        let defines = defines(&[("WIN32", Some("1")), ("DEBUG", None), ("LEVEL", Some("0"))]);
+        assert_eq!(eval_condition("defined(WIN32)", &defines), Some(true));
+        assert_eq!(eval_condition("defined DEBUG", &defines), Some(false));
+        assert_eq!(
+            eval_condition("!defined(DEBUG) && WIN32", &defines),
+            Some(true)
+        );
+        assert_eq!(eval_condition("LEVEL || (DEBUG)", &defines), Some(false));
+        assert_eq!(eval_condition("0 // disabled", &defines), Some(false));
+        assert_eq!(eval_condition("1L", &defines), Some(true));
+    }
+
+    #[test]
+    fn unknown_names_leave_the_condition_open() {
+        let defines = defines(&[("WIN32", Some("1"))]);
+        assert_eq!(eval_condition("defined(__APPLE__)", &defines), None);
+        assert_eq!(
+            eval_condition("defined(__APPLE__) || WIN32", &defines),
+            Some(true)
+        );
+        assert_eq!(
+            eval_condition("defined(__APPLE__) && !WIN32", &defines),
+            Some(false)
+        );
+        assert_eq!(eval_condition("VERSION > 2", &defines), None);
+    }
+}
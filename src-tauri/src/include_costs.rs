@@ -0,0 +1,79 @@
+use serde::Serialize;
+use std::{collections::HashMap, fs};
+
+use crate::data::get_language_key;
+
+/// headers ranked for every file
+const TOP_HEADERS: usize = 5;
+
+/// what the includes of a c / c++ file make the compiler read
+#[derive(Debug, Serialize, Clone)]
+pub struct IncludeCost {
+    pub file: usize,
+    /// files it includes, directly or through other headers
+    pub transitive_includes: usize,
+    /// lines of all of them, read besides the file itself
+    pub included_lines: usize,
+    /// `(header, lines)` of the headers pulling in the most lines, theirs and
+    /// the ones of what they include, the most first
+    pub top_headers: Vec<(usize, usize)>,
+}
+
+/// the include cost of every c / c++ file, the most expensive first
+///
+/// `include_closure` is every file each file sees through its includes, a
+/// header is weighed with its own closure, so a header including the others
+/// ranks above them
+pub fn include_costs(
+    all_files: &[String],
+    include_closure: &HashMap<usize, Vec<usize>>,
+) -> Vec<IncludeCost> {
+    let is_c = |f: usize| {
+        matches!(
+            get_language_key(&all_files[f]).as_deref(),
+            Some("c" | "cpp")
+        )
+    };
+    let lines = (0..all_files.len())
+        .map(|f| match is_c(f) {
+            true => fs::read_to_string(&all_files[f]).map_or(0, |content| content.lines().count()),
+            false => 0,
+        })
+        .collect::<Vec<usize>>();
+    let no_includes = vec![];
+
+    let mut costs = (0..all_files.len())
+        .filter(|f| is_c(*f))
+        .map(|file| {
+            let closure = include_closure.get(&file).unwrap_or(&no_includes);
+            let mut top_headers = closure
+                .iter()
+                .map(|header| {
+                    // a cycle back to the file does not add to it
+                    let pulled_in = include_closure
+                        .get(header)
+                        .unwrap_or(&no_includes)
+                        .iter()
+                        .filter(|included| **included != file)
+                        .map(|included| lines[*included])
+                        .sum::<usize>();
+                    (*header, lines[*header] + pulled_in)
+                })
+                .collect::<Vec<(usize, usize)>>();
+            top_headers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            top_headers.truncate(TOP_HEADERS);
+            IncludeCost {
+                file,
+                transitive_includes: closure.len(),
+                included_lines: closure.iter().map(|f| lines[*f]).sum(),
+                top_headers,
+            }
+        })
+        .collect::<Vec<IncludeCost>>();
+    costs.sort_by(|a, b| {
+        b.included_lines
+            .cmp(&a.included_lines)
+            .then(a.file.cmp(&b.file))
+    });
+    costs
+}
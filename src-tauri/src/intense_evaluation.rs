@@ -1,9 +1,23 @@
-use crate::{data::*, evaluate_imports::read_all_imports};
+use crate::{
+    code_lexer::{SourceFile, SourceFiles},
+    data::*,
+    evaluate_imports::{symbol_imports, ImportedNames},
+    qualified_names::{join, lookup_candidates, read_using_directives, resolve, simple_name},
+    span::{LineIndex, Span},
+    tag_entry::{Access, ClassKind},
+    type_ref::{
+        base_name, generic_params, go_signature, matching_angle, split_top_level,
+        strip_template_clause,
+    },
+};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::usize;
-use std::{collections::HashMap, fmt, fs};
+use std::{collections::HashMap, fmt};
+
+mod built_in_tags;
+pub use built_in_tags::extract_project_tags;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum StatefulClassConnection {
@@ -33,28 +47,50 @@ enum CodeElementPointer {
     Ambiguous(usize, usize, String),
 }
 
-/// start, end, parent, \[bracket/curly/square/root\], content
-pub struct SCOPE(usize, usize, usize, i8, String);
+/// start, end, parent, \[bracket/curly/square/root\], content, span
+///
+/// start and end are the byte offsets of the opening and closing brackets,
+/// the span covers both of them
+pub struct SCOPE(usize, usize, usize, i8, String, Span);
 
 /// start, end, code element pointers
 pub struct CHILDACCESS(usize, usize, Vec<CodeElementPointer>);
 
-/// start, name, vars_scope, vars
-pub struct FUNCTIONCALL(usize, String, usize, Vec<CodeElementPointer>);
+/// start, name, vars_scope, vars, span from the name to the closing `)`
+pub struct FUNCTIONCALL(usize, String, usize, Vec<CodeElementPointer>, Span);
 
-/// lhs(start, str), rhs(start, str)
-pub struct EQUATION((usize, String), (usize, String));
+/// lhs(start, str), rhs(start, str), span of the whole statement
+pub struct EQUATION((usize, String), (usize, String), Span);
 
-/// scope, name, [(parent_scope, parents)]
-pub struct CLASS(usize, String, Vec<(String, String)>);
+/// scope, name, [(access, parent)], span from `class` to the closing `}`, kind, [template params],
+/// partial
+///
+/// structs, unions, enums and namespaces are classes of another kind
+pub struct CLASS(
+    usize,
+    String,
+    Vec<(Access, String)>,
+    Span,
+    ClassKind,
+    Vec<String>,
+    bool,
+);
 
-/// scope, name, return_type, [args], name_pos
+/// scope, name, return_type, [args], name_pos, span from the return type to the closing `}`, [template params],
+/// go signature, pointer receiver
+///
+/// the name of an out of line definition keeps its class, `Mammal::getAge`,
+/// name_pos is where the simple name starts
 pub struct FUNCTION(
     usize,
     String,
     String,
     Vec<(StatefulClassConnection, String)>,
     usize,
+    Span,
+    Vec<String>,
+    Option<String>,
+    bool,
 );
 
 /// scope, [imports as args], [args]
@@ -64,8 +100,8 @@ pub struct LAMBDA(
     Vec<(StatefulClassConnection, String)>,
 );
 
-/// parent_scope, name, type
-pub struct OBJECT(usize, String, String);
+/// parent_scope, name, type, span of the declaration
+pub struct OBJECT(usize, String, String, Span);
 
 impl Queryable for CLASS {
     fn get_query(&self) -> Option<String> {
@@ -119,7 +155,7 @@ macro_rules! build_regex_vec_from_res {
         let regex_strs = regex_res.unwrap();
         let mut regex_vec = vec![];
 
-        for regex_str in regex_strs.into_iter() {
+        for regex_str in regex_strs.iter() {
             if let Ok(regex) = Regex::new(regex_str) {
                 regex_vec.push(regex);
             } else {
@@ -134,9 +170,14 @@ macro_rules! build_regex_vec_from_res {
 /// START ///////////////////////////////////////////////////////////////////////////////////
 /////////////////////////////////////////////////////////////////////////////////////////////
 
+/// `imported_files` and `imported_names` are what the files import, `visible_files`
+/// what they see through the chains of headers, as the tags pass read them
 pub fn evaluate(
-    project_path: &String,
     all_files: &Vec<&String>,
+    sources: &SourceFiles,
+    imported_files: &HashMap<usize, Vec<usize>>,
+    imported_names: &ImportedNames,
+    visible_files: &HashMap<usize, Vec<usize>>,
 ) -> (
     HashMap<usize, Vec<(String, usize)>>,
     HashMap<usize, HashMap<usize, Vec<(usize, usize)>>>,
@@ -144,11 +185,21 @@ pub fn evaluate(
 ) {
     let mut intense_info = Vec::new();
     for (file_i, file) in all_files.iter().enumerate() {
-        if let Some(info) = language_file_intense_extract(file_i, file) {
+        let info = sources
+            .get(*file)
+            .and_then(|source| language_file_intense_extract(file_i, file, source));
+        if let Some(info) = info {
             intense_info.push(info);
         }
     }
-    create_scope_availability(project_path, all_files, &intense_info)
+    create_scope_availability(
+        all_files,
+        sources,
+        imported_files,
+        imported_names,
+        visible_files,
+        &intense_info,
+    )
 }
 
 // pub fn evaluate(project_path: &String, all_files: &Vec<&String>) {
@@ -177,6 +228,7 @@ pub fn evaluate(
 fn language_file_intense_extract(
     file_i: usize,
     file_path: &String,
+    source: &SourceFile,
 ) -> Option<(
     Vec<SCOPE>,
     Vec<CHILDACCESS>,
@@ -190,10 +242,10 @@ fn language_file_intense_extract(
     println!();
     println!("in file {}", file_path);
 
-    let file_text = match fs::read_to_string(file_path) {
-        Ok(f) => f,
-        Err(_) => String::new(),
-    };
+    let file_text = &source.text;
+    // comments, literals and skipped `#else` branches blanked out, same positions as `file_text`
+    let code_text = &source.code;
+    let positions = LineIndex::new(file_text);
 
     // scopes evaluation
 
@@ -202,10 +254,54 @@ fn language_file_intense_extract(
     let mut scope_stack: Vec<usize> = Vec::new();
     // region create file level scope
 
-    scope_entries.push(SCOPE(0, file_text.len(), usize::MAX, 3, file_text.clone()));
+    scope_entries.push(SCOPE(
+        0,
+        file_text.len(),
+        usize::MAX,
+        3,
+        file_text.clone(),
+        positions.span(0, file_text.len()),
+    ));
     scope_stack.push(0);
 
-    for (c_i, c) in file_text.chars().enumerate() {
+    // python blocks have no braces, `(start, end)` from the `:` to the end of their last line,
+    // neither has the file scoped `namespace Zoo;` of c#, from its `;` to the end of the file
+    let blocks = if has_indented_blocks(file_path) {
+        indentation_blocks(&code_text)
+    } else if get_language_key(file_path).as_deref() == Some("cs") {
+        file_scoped_namespace(&code_text)
+    } else {
+        Vec::new()
+    };
+    let mut next_block = 0;
+    // scope -> end of its indentation block
+    let mut block_ends: HashMap<usize, usize> = HashMap::new();
+
+    for (c_i, c) in code_text.char_indices() {
+        while let Some(&top) = scope_stack.last() {
+            match block_ends.get(&top) {
+                Some(&end) if end <= c_i => {
+                    scope_entries[top].1 = end;
+                    scope_stack.pop();
+                }
+                _ => break,
+            }
+        }
+        if blocks.get(next_block).map_or(false, |b| b.0 == c_i) {
+            scope_entries.push(SCOPE(
+                c_i,
+                0,
+                *scope_stack.last().unwrap_or(&usize::MAX),
+                1,
+                "".to_string(),
+                Span::default(),
+            ));
+            let new_scope_idx = scope_entries.len() - 1;
+            block_ends.insert(new_scope_idx, blocks[next_block].1);
+            scope_stack.push(new_scope_idx);
+            next_block += 1;
+            continue;
+        }
         if c == '(' || c == '{' || c == '[' {
             scope_entries.push(SCOPE(
                 c_i,
@@ -222,6 +318,7 @@ fn language_file_intense_extract(
                     _ => 5,
                 },
                 "".to_string(),
+                Span::default(),
             ));
             let new_scope_idx = scope_entries.len() - 1;
             scope_stack.push(new_scope_idx);
@@ -237,7 +334,19 @@ fn language_file_intense_extract(
     let scopes_len = scope_entries.len();
     for i in 0..scopes_len {
         let s = scope_entries.get(i).unwrap();
-        scope_entries[i].4 = file_text[s.0 + 1..s.1].chars().collect();
+        // a scope left open by unbalanced braces runs to the end of the file
+        let end = if s.1 > s.0 { s.1 } else { file_text.len() };
+        // the closing bracket is part of the span, the file scope and the
+        // indentation blocks have none
+        let span_end = if s.3 == 3 || end == file_text.len() || block_ends.contains_key(&i) {
+            end
+        } else {
+            end + 1
+        };
+        let start = s.0;
+        scope_entries[i].4 = file_text.get(start + 1..end).unwrap_or("").to_string();
+        scope_entries[i].5 = positions.span(start, span_end);
+        scope_entries[i].1 = end;
     }
 
     // -------------------------------------------------------------------------------------------------------//
@@ -279,68 +388,39 @@ fn language_file_intense_extract(
                         .collect::<String>()
                         .trim()
                         .to_string();
-                    let rhs = rhs_with_colon[..rhs_with_colon.len() - 1]
+                    let rhs = rhs_with_colon
+                        .strip_suffix(';')
+                        .unwrap_or(&rhs_with_colon)
                         .trim()
                         .to_string();
                     let rhs_offset = equation.find(&rhs_with_colon).unwrap();
                     equation_entries.push(EQUATION(
                         (eq_match.start(), lhs),
                         (eq_match.start() + rhs_offset, rhs),
+                        positions.span(trimmed_start(&eq_match), eq_match.end()),
                     ));
                 }
             }
         }
     }
     let mut class_entries: Vec<CLASS> = Vec::new();
+    let csharp = get_language_key(file_path).as_deref() == Some("cs");
+    let go = get_language_key(file_path).as_deref() == Some("go");
     for c in class_regex {
-        for caps in c.captures_iter(&file_text) {
+        for caps in c.captures_iter(&code_text) {
             // caps.iter().for_each(|x| print!("{x:?}"));
             if let Some(class_def_m) = &caps.get(0) {
-                let mut class_name = "".to_string();
-                let mut class_parents = vec![];
-
-                let class_def = class_def_m.as_str();
-                if let Some(parents_def_pos) = class_def.find(':') {
-                    let class_part = class_def[..parents_def_pos]
-                        .trim()
-                        .strip_prefix("class")
-                        .unwrap()
-                        .to_string();
-                    class_name = class_part.trim().to_string();
-                    let c_parents = &caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                    let mut comma_pos = Vec::new();
-                    let args_str = c_parents.to_string();
-                    args_str.chars().enumerate().for_each(|(i, c)| {
-                        if c == ',' {
-                            comma_pos.push(i);
-                        }
-                    });
-                    let mut prev_comma_pos = 0 as usize;
-                    let mut args_type_and_names = Vec::new();
-                    comma_pos.iter().for_each(|p| {
-                        args_type_and_names
-                            .push(args_str[prev_comma_pos..p.clone()].trim().to_string());
-                        prev_comma_pos = p + 1;
-                    });
-                    args_type_and_names.push(args_str[prev_comma_pos..].trim().to_string());
-                    let mut args = Vec::new();
-                    args_type_and_names.iter().for_each(|t_n| {
-                        if let Some(sep_pos) = t_n.rfind(' ') {
-                            let arg_type = t_n[..sep_pos].trim().to_string();
-                            let arg_name = t_n[sep_pos + 1..].trim().to_string();
-                            args.push((arg_type, arg_name));
-                        }
-                    });
-                    class_parents = args;
-                } else {
-                    let class_part = class_def[..class_def.len() - 1].trim().to_string();
-                    if let Some(c_name) = class_part.strip_prefix("class") {
-                        class_name = c_name.trim().to_string();
-                    }
-                }
+                let kind = match caps
+                    .name("keyword")
+                    .and_then(|k| ClassKind::from_keyword(k.as_str()))
+                {
+                    Some(k) => k,
+                    None => continue,
+                };
 
-                let scope_start_pos = class_def_m.end();
-                let class_scope = scope_entries
+                // a kotlin class without a body is scoped by its constructor
+                let scope_start_pos = caps.name("body").map_or(class_def_m.end(), |b| b.end());
+                let class_scope = match scope_entries
                     .iter()
                     .enumerate()
                     .filter(|(_, x)| x.0 == scope_start_pos - 1)
@@ -349,9 +429,75 @@ fn language_file_intense_extract(
                             return Some(x.0);
                         }
                         None
-                    })
-                    .unwrap();
-                class_entries.push(CLASS(class_scope, class_name, class_parents));
+                    }) {
+                    Some(s) => s,
+                    None => continue,
+                };
+
+                let (mut template_params, definition) = strip_template_clause(class_def_m.as_str());
+                if let Some(generics) = caps.name("generics") {
+                    template_params = generic_params(generics.as_str());
+                }
+                let class_name = match caps.name("name") {
+                    // `impl<T> fmt::Display for Wrapper<T>` is a body of `Wrapper`
+                    Some(name) if kind == ClassKind::Implementation => {
+                        simple_name(&base_name(name.as_str())).to_string()
+                    }
+                    // `namespace Zoo.Animals` is nested like `namespace Zoo::Animals`
+                    Some(name) if csharp => name.as_str().replace('.', "::"),
+                    Some(name) => name.as_str().to_string(),
+                    // `typedef struct { .. } Name;` is named after its body
+                    None if definition.starts_with("typedef") => {
+                        match name_after_scope(&code_text, scope_entries[class_scope].1) {
+                            Some(name) => name,
+                            None => continue,
+                        }
+                    }
+                    // anonymous namespaces, structs and unions
+                    None => continue,
+                };
+                // the `: type` of an enum is its underlying type, not a parent
+                let mut class_parents = match caps.name("parents") {
+                    Some(parents) if kind != ClassKind::Enum => {
+                        extract_parents(parents.as_str(), default_base_access(file_path, kind))
+                    }
+                    _ => Vec::new(),
+                };
+                // typescript lists the interfaces apart from the base class
+                if let Some(implemented) = caps.name("implements") {
+                    class_parents.extend(extract_parents(
+                        implemented.as_str(),
+                        default_base_access(file_path, kind),
+                    ));
+                }
+                if csharp {
+                    for (_, parent) in class_parents.iter_mut() {
+                        *parent = parent.replace('.', "::");
+                    }
+                }
+                // a go type lists no bases, it embeds them
+                if go {
+                    let scope = &scope_entries[class_scope];
+                    class_parents = code_text
+                        .get(scope.0 + 1..scope.1)
+                        .map_or(Vec::new(), embedded_types)
+                        .into_iter()
+                        .map(|t| (Access::Public, t))
+                        .collect();
+                }
+
+                let span =
+                    positions.span(trimmed_start(class_def_m), scope_entries[class_scope].5.end);
+                class_entries.push(CLASS(
+                    class_scope,
+                    class_name,
+                    class_parents,
+                    span,
+                    kind,
+                    template_params,
+                    // c# `partial class Dog` is one of the parts of `Dog`
+                    caps.name("partial").is_some(),
+                ));
             } else {
                 // eprintln!("couldn't parse for class\n{}", )
             }
@@ -360,8 +506,72 @@ fn language_file_intense_extract(
     let mut function_entries: Vec<FUNCTION> = Vec::new();
     for f in funs_regex {
         // println!("regex {}", f.as_str());
-        for caps in f.captures_iter(&file_text) {
+        for caps in f.captures_iter(&code_text) {
             // println!("captured: {:?}", caps);
+            // rust names the parts, its `pub(crate)` and `<F: Fn(u32)>` hold brackets too,
+            // so do typescript and `x => {` has its only arg unbracketed
+            if let (Some(m), Some(name), Some(inside_b)) = (
+                caps.get(0),
+                caps.name("name"),
+                caps.name("args").or(caps.name("param")),
+            ) {
+                // `if (ready) {` at the start of a line looks like a method, so does
+                // the anonymous class of `new Thread() {`
+                if CONTROL_KEYWORDS.contains(&name.as_str())
+                    || caps.name("ret").map_or(false, |r| r.as_str() == "new")
+                {
+                    continue;
+                }
+                // a method of a go interface is scoped by its `(..)`
+                let scope_start_pos = caps.name("body").map_or(m.end(), |b| b.end());
+                let fun_scope = match scope_entries
+                    .iter()
+                    .position(|x| x.0 == scope_start_pos - 1)
+                {
+                    Some(s) => s,
+                    None => continue,
+                };
+                // a call on a line of its own looks like one too
+                if caps.name("body").is_some()
+                    && !class_entries
+                        .iter()
+                        .any(|c| c.0 == scope_entries[fun_scope].2 && c.4 == ClassKind::Interface)
+                {
+                    continue;
+                }
+                let def_start = trimmed_start(&m);
+                let ret = caps
+                    .name("ret")
+                    .map_or(String::new(), |r| r.as_str().trim().to_string());
+                // a go interface is satisfied by the methods of the same signature
+                let signature = match go {
+                    true => Some(go_signature(inside_b.as_str(), &ret)),
+                    false => None,
+                };
+                let pointer_receiver = caps
+                    .name("receiver")
+                    .map_or(false, |r| r.as_str().contains('*'));
+                function_entries.push(FUNCTION(
+                    fun_scope,
+                    // `func (r *Repo) Save() {` is `Save` defined out of line for `Repo`
+                    match caps.name("receiver") {
+                        Some(receiver) => join(&go_type(receiver.as_str()), name.as_str()),
+                        None => name.as_str().to_string(),
+                    },
+                    match go {
+                        true => go_type(&ret),
+                        false => ret,
+                    },
+                    extract_args(inside_b.as_str().to_string()),
+                    name.start(),
+                    positions.span(def_start, scope_entries[fun_scope].5.end),
+                    caps.name("generics")
+                        .map_or(Vec::new(), |g| generic_params(g.as_str())),
+                    signature,
+                    pointer_receiver,
+                ));
+                continue;
+            }
             caps.iter().for_each(|x| {
                 if let Some(m) = x {
                     let m_str = m.as_str();
@@ -373,10 +583,20 @@ fn language_file_intense_extract(
                     }
                     let bo_pos = bo_pos_o.unwrap();
                     let bc_pos = bc_pos_o.unwrap();
-                    let type_and_name = def[..bo_pos].to_string();
+                    // `public:` before a member is not part of its return type
+                    let label_len = access_label_len(&def[..bo_pos]);
+                    // so is the `template<..>` clause, its parameters are kept apart
+                    let (template_params, type_and_name) =
+                        strip_template_clause(&def[label_len..bo_pos]);
+                    let type_and_name = type_and_name.trim_start();
+                    let def_start = m.start() + bo_pos - type_and_name.len();
+                    let type_and_name = type_and_name.to_string();
                     let type_name_separator_pos = type_and_name.rfind(" ").unwrap_or(0);
                     let (type_, name) = (
-                        type_and_name[..type_name_separator_pos].to_string(),
+                        return_type(
+                            &type_and_name[..type_name_separator_pos],
+                            &def[bc_pos + 1..],
+                        ),
                         type_and_name[type_name_separator_pos + 1..].to_string(),
                     );
                     let inside_b = def[bo_pos + 1..bc_pos].to_string();
@@ -384,7 +604,7 @@ fn language_file_intense_extract(
 
                     let scope_start_pos = m.end();
 
-                    let fun_scope = scope_entries
+                    let fun_scope = match scope_entries
                         .iter()
                         .enumerate()
                         .filter(|(_, x)| x.0 == scope_start_pos - 1)
@@ -393,51 +613,169 @@ fn language_file_intense_extract(
                                 return Some(x.0);
                             }
                             None
-                        })
-                        .unwrap();
+                        }) {
+                        Some(s) => s,
+                        None => return,
+                    };
+                    let span = positions.span(def_start, scope_entries[fun_scope].5.end);
+                    let name_pos = def_start + type_name_separator_pos + 1 + name.len()
+                        - simple_name(&name).len();
                     function_entries.push(FUNCTION(
                         fun_scope,
                         name,
                         type_,
                         args,
-                        m.start() + type_name_separator_pos + 1,
+                        name_pos,
+                        span,
+                        template_params,
+                        None,
+                        false,
                     ));
                 }
             });
         }
     }
     let mut lambda_entries: Vec<LAMBDA> = Vec::new();
+    let when_regex = Regex::new(r"\bwhen\b[^{}]*$").unwrap();
     for l in lambdas_regex {
-        for caps in l.captures_iter(&file_text) {
+        for caps in l.captures_iter(&code_text) {
             if let Some(def_match) = caps.get(0) {
                 let def_str = def_match.as_str();
-                let imports_end_pos = def_str.find("]").unwrap();
-                let imports_str = def_str[1..imports_end_pos].to_string();
-                let imports = extract_args(imports_str);
-                let args_start_pos = def_str.find("(").unwrap();
-                let args_end_pos = def_str.rfind(")").unwrap();
-                let args_str = def_str[args_start_pos + 1..args_end_pos].to_string();
-                let args = extract_args(args_str);
-
-                let scope_start_pos = def_match.end();
-                let fun_scope = scope_entries
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, x)| x.0 == scope_start_pos - 1)
-                    .find_map(|x| {
-                        if x.1 .0 == scope_start_pos - 1 {
-                            return Some(x.0);
-                        }
-                        None
-                    })
-                    .unwrap();
+                // `[captures](args) {`, python `lambda args:` or `x => ..`, where the
+                // args are the first group that took part
+                let (imports, args) = match (
+                    def_str.starts_with('['),
+                    def_str.find("]"),
+                    def_str.find("("),
+                    def_str.rfind(")"),
+                ) {
+                    (true, Some(imports_end_pos), Some(args_start_pos), Some(args_end_pos)) => (
+                        extract_args(def_str[1..imports_end_pos].to_string()),
+                        extract_args(def_str[args_start_pos + 1..args_end_pos].to_string()),
+                    ),
+                    _ => match caps.iter().skip(1).flatten().next() {
+                        Some(args) => (Vec::new(), extract_args(args.as_str().to_string())),
+                        None => continue,
+                    },
+                };
+
+                // a kotlin lambda `{ a, b -> .. }` opens its body before its args
+                let body_start = if def_str.starts_with('{') {
+                    def_match.start()
+                } else {
+                    def_match.end() - 1
+                };
+                // the branches of a kotlin `when (x) {` look like one
+                let line_start = code_text[..body_start].rfind('\n').map_or(0, |n| n + 1);
+                if def_str.starts_with('{')
+                    && when_regex.is_match(&code_text[line_start..body_start])
+                {
+                    continue;
+                }
+                // so does the body of a c# member, `int Age => age;` or `Area() => w * h;`
+                let def_line_start = code_text[..def_match.start()]
+                    .rfind('\n')
+                    .map_or(0, |n| n + 1);
+                if csharp && is_member_arrow(&code_text[def_line_start..def_match.start()]) {
+                    continue;
+                }
+                let body = scope_entries.iter().position(|x| x.0 == body_start);
+                // the body of `const add = (a, b) => {` is already a function
+                if body.map_or(false, |b| function_entries.iter().any(|f| f.0 == b)) {
+                    continue;
+                }
+                // a lambda without a body of its own
+                let fun_scope =
+                    body.unwrap_or_else(|| find_parent(&def_match.start(), &scope_entries));
                 lambda_entries.push(LAMBDA(fun_scope, imports, args));
             }
         }
     }
     let mut object_entries: Vec<OBJECT> = Vec::new();
+    let template_clauses = template_clauses(&code_text);
+    // the members of an enum are its enumerators, not fields
+    let class_scopes = class_entries
+        .iter()
+        .filter(|c| c.4 != ClassKind::Enum)
+        .map(|c| c.0)
+        .collect::<Vec<usize>>();
+    let indented = has_indented_blocks(file_path);
     for o in objs_regex {
-        for caps in o.captures_iter(&file_text) {
+        for caps in o.captures_iter(&code_text) {
+            // python and rust patterns name their parts, the type may have to be read from the value
+            if let (Some(_match), Some(name)) = (
+                caps.get(0),
+                caps.name("name")
+                    .or(caps.name("field"))
+                    .or(caps.name("property")),
+            ) {
+                // `const load = () => {` is a function
+                if function_entries.iter().any(|f| f.4 == name.start()) {
+                    continue;
+                }
+                let name = name.as_str().to_string();
+                if PYTHON_KEYWORDS.contains(&name.as_str())
+                    || caps
+                        .name("type")
+                        .map_or(false, |t| STATEMENT_KEYWORDS.contains(&t.as_str()))
+                {
+                    continue;
+                }
+                let type_str = match (caps.name("type"), caps.name("value")) {
+                    (Some(t), _) if go => go_type(t.as_str()),
+                    (Some(t), _) => t.as_str().trim().to_string(),
+                    (None, Some(v)) => {
+                        value_type(v.as_str(), get_language_key(file_path).as_deref())
+                    }
+                    (None, None) => String::new(),
+                };
+                let mut parent_scope = find_parent(&_match.start(), &scope_entries);
+                // `name: String,` is a field only in the body of a struct
+                if caps.name("field").is_some() && !class_scopes.contains(&parent_scope) {
+                    continue;
+                }
+                // `name="x",` on a line of a call or a literal is an argument, not an
+                // assignment, the brackets are the only scopes that are no block
+                if indented
+                    && scope_entries[parent_scope].3 != 3
+                    && !block_ends.contains_key(&parent_scope)
+                {
+                    continue;
+                }
+                // a `property` of a kotlin constructor belongs to the class it constructs
+                if caps.name("property").is_some() {
+                    let constructor = find_parent(&(_match.start() + 1), &scope_entries);
+                    let constructor_start = scope_entries[constructor].0;
+                    match class_entries.iter().find(|c| {
+                        c.0 == constructor
+                            || (c.3.start <= constructor_start
+                                && constructor_start < scope_entries[c.0].0)
+                    }) {
+                        Some(c) => parent_scope = c.0,
+                        None => continue,
+                    }
+                }
+                // `self.name = ..` in a method belongs to the class around the method
+                if _match.as_str().trim_start().starts_with("self.") {
+                    let mut s = parent_scope;
+                    while s != usize::MAX && !class_scopes.contains(&s) {
+                        s = scope_entries[s].2;
+                    }
+                    if s != usize::MAX {
+                        parent_scope = s;
+                    }
+                }
+                // assigned again, the first assignment declares it
+                if object_entries
+                    .iter()
+                    .any(|obj| obj.0 == parent_scope && obj.1 == name)
+                {
+                    continue;
+                }
+                let span = positions.span(trimmed_start(&_match), _match.end());
+                object_entries.push(OBJECT(parent_scope, name, type_str, span));
+                continue;
+            }
             if let Some(_match) = caps.get(0) {
                 let match_str = _match.as_str()[.._match.as_str().len() - 1]
                     .trim()
@@ -447,16 +785,28 @@ fn language_file_intense_extract(
                     if type_str == "public".to_string() || type_str == "private".to_string() {
                         continue;
                     }
+                    // `using namespace std;` is not an object
+                    if type_str.starts_with("using") {
+                        continue;
+                    }
+                    // `typename T, int N` of `template<typename T, int N>` are parameters
+                    if template_clauses
+                        .iter()
+                        .any(|(start, end)| _match.start() > *start && _match.start() < *end)
+                    {
+                        continue;
+                    }
                     let name = match_str[type_name_space_pos..].trim().to_string();
                     let parent_scope = find_parent(&_match.start(), &scope_entries);
-                    object_entries.push(OBJECT(parent_scope, name, type_str));
+                    let span = positions.span(trimmed_start(&_match), _match.end());
+                    object_entries.push(OBJECT(parent_scope, name, type_str, span));
                 }
             }
         }
     }
     let mut function_call_entries: Vec<FUNCTIONCALL> = Vec::new();
     for f in function_call_regex {
-        for caps in f.find_iter(&file_text) {
+        for caps in f.find_iter(&code_text) {
             let mut is_declaration = false;
             for f_n in &function_entries {
                 if caps.start() == f_n.4 {
@@ -464,7 +814,14 @@ fn language_file_intense_extract(
                     break;
                 }
             }
-            if is_declaration {
+            // `class Dog(Animal):` lists the bases, `struct Id(u32);` and `record Id(int v)`
+            // the fields, they call nothing
+            let before = code_text[..caps.start()].trim_end();
+            if is_declaration
+                || before.ends_with("class")
+                || before.ends_with("struct")
+                || before.ends_with("record")
+            {
                 continue;
             }
             if let Some(vars_scope_i) = scope_entries.iter().enumerate().find_map(|(s_i, s)| {
@@ -477,19 +834,20 @@ fn language_file_intense_extract(
                 let mut vars = Vec::new();
                 let vars_scope = scope_entries.iter().nth(vars_scope_i).unwrap();
                 let vars_scope_str = vars_scope.4.clone();
+                let vars_bytes = vars_scope_str.as_bytes();
                 let start = vars_scope.0;
                 let mut current_char_i = 0 as usize;
 
                 while current_char_i < vars_scope_str.len() {
-                    while vars_scope_str.chars().nth(current_char_i).unwrap() == ' ' {
+                    while current_char_i < vars_bytes.len() && vars_bytes[current_char_i] == b' ' {
                         current_char_i += 1;
                     }
                     let var_start = current_char_i;
                     while current_char_i < vars_scope_str.len()
-                        && (vars_scope_str.chars().nth(current_char_i).unwrap() != ','
-                            || vars_scope_str.chars().nth(current_char_i).unwrap() != ')')
+                        && (vars_bytes[current_char_i] != b','
+                            || vars_bytes[current_char_i] != b')')
                     {
-                        if vars_scope_str.chars().nth(current_char_i).unwrap() == '(' {
+                        if vars_bytes[current_char_i] == b'(' {
                             if let Some(call_scope) = scope_entries
                                 .iter()
                                 .filter_map(|s| {
@@ -510,12 +868,22 @@ fn language_file_intense_extract(
                     vars.push(CodeElementPointer::Ambiguous(
                         file_i,
                         start + var_start,
-                        vars_scope_str[var_start..current_char_i].to_string(),
+                        vars_scope_str
+                            .get(var_start..current_char_i.min(vars_scope_str.len()))
+                            .unwrap_or("")
+                            .to_string(),
                     ));
                 }
 
                 println!("function args str :{}", vars_scope);
-                function_call_entries.push(FUNCTIONCALL(caps.start(), fn_name, vars_scope_i, vars));
+                let span = positions.span(caps.start(), vars_scope.5.end);
+                function_call_entries.push(FUNCTIONCALL(
+                    caps.start(),
+                    fn_name,
+                    vars_scope_i,
+                    vars,
+                    span,
+                ));
             } else {
                 println!("error in finding vars_scope for query :{:?}", caps);
             }
@@ -526,6 +894,8 @@ fn language_file_intense_extract(
         for caps in a.find_iter(&file_text) {
             // TODO: the ending points of the different elements can also be stored somewhere to make this easier.
             let start = caps.start();
+            // regex positions are byte offsets
+            let char_at = |i: usize| file_text.get(i..).and_then(|rest| rest.chars().next());
             let mut pointers = Vec::new();
             let mut prev_char_i = 0;
             let mut curr_char_i = start;
@@ -534,8 +904,8 @@ fn language_file_intense_extract(
             while true {
                 found_something = false;
                 // skip the spaces
-                while file_text.chars().nth(curr_char_i).unwrap_or(';') == ' '
-                    || file_text.chars().nth(curr_char_i).unwrap_or(';') == '.'
+                while char_at(curr_char_i).unwrap_or(';') == ' '
+                    || char_at(curr_char_i).unwrap_or(';') == '.'
                 {
                     curr_char_i += 1;
                 }
@@ -545,8 +915,8 @@ fn language_file_intense_extract(
                 prev_char_i = curr_char_i;
 
                 // end if found ant thing that should brake the access [ lang specific ]
-                if file_text.chars().nth(curr_char_i).unwrap_or(';') == ';'
-                    || file_text.chars().nth(curr_char_i).unwrap_or(';') == '='
+                if char_at(curr_char_i).unwrap_or(';') == ';'
+                    || char_at(curr_char_i).unwrap_or(';') == '='
                 {
                     break;
                 }
@@ -566,10 +936,10 @@ fn language_file_intense_extract(
                 let mut word_or_something = "".to_string();
                 let mut word_found = false;
                 while !found_something && !word_found {
-                    if let Some(curr_char) = file_text.chars().nth(curr_char_i) {
+                    if let Some(curr_char) = char_at(curr_char_i) {
                         if curr_char.is_alphanumeric() || curr_char == '_' {
                             word_or_something.push(curr_char);
-                            curr_char_i += 1;
+                            curr_char_i += curr_char.len_utf8();
                             continue;
                         }
                         pointers.push(CodeElementPointer::Object(
@@ -578,6 +948,8 @@ fn language_file_intense_extract(
                         ));
                         found_something = true;
                         word_found = true;
+                    } else {
+                        break;
                     }
                 }
             }
@@ -606,22 +978,17 @@ fn language_file_intense_extract(
 }
 
 fn extract_args(args_str: String) -> Vec<(StatefulClassConnection, String)> {
-    let mut comma_pos = Vec::new();
-    args_str.chars().enumerate().for_each(|(i, c)| {
-        if c == ',' {
-            comma_pos.push(i);
-        }
-    });
-    let mut prev_comma_pos = 0 as usize;
-    let mut args_type_and_names = Vec::new();
-    comma_pos.iter().for_each(|p| {
-        args_type_and_names.push(args_str[prev_comma_pos..p.clone()].trim().to_string());
-        prev_comma_pos = p + 1;
-    });
-    args_type_and_names.push(args_str[prev_comma_pos..].trim().to_string());
+    // `std::map<int, int> m` and `f: HashMap<K, V>` are one arg each
+    let args_type_and_names = split_top_level(&args_str, ',')
+        .into_iter()
+        .map(|t_n| t_n.trim().to_string())
+        .collect::<Vec<String>>();
     let mut args = Vec::new();
     args_type_and_names.iter().for_each(|t_n| {
-        if let Some(sep_pos) = t_n.rfind(' ') {
+        // python `name: str = ""`, `*args: int`
+        if let Some((name, type_)) = annotated_arg(t_n) {
+            args.push((StatefulClassConnection::Undiscovered(type_), name));
+        } else if let Some(sep_pos) = t_n.rfind(' ') {
             let arg_type = t_n[..sep_pos].trim().to_string();
             let arg_name = t_n[sep_pos + 1..].trim().to_string();
             args.push((StatefulClassConnection::Undiscovered(arg_type), arg_name));
@@ -630,10 +997,498 @@ fn extract_args(args_str: String) -> Vec<(StatefulClassConnection, String)> {
     args
 }
 
+/// `name: str = ""` -> `("name", "str")`, `mut dogs: Vec<Dog>` -> `("dogs", "Vec<Dog>")`,
+/// none when the arg is not annotated
+fn annotated_arg(arg: &str) -> Option<(String, String)> {
+    let declaration = split_top_level(arg, '=')[0];
+    let colon = declaration.find(':')?;
+    // `std::string s` is a c++ arg
+    if declaration[colon..].starts_with("::") {
+        return None;
+    }
+    let name = declaration[..colon].trim().trim_start_matches('*');
+    let name = name.strip_prefix("mut ").unwrap_or(name).trim();
+    let type_ = declaration[colon + 1..].trim();
+    if name.is_empty() || type_.is_empty() {
+        return None;
+    }
+    Some((name.to_string(), type_.to_string()))
+}
+
+/// words in front of a function name that define it rather than type it
+const FUNCTION_KEYWORDS: [&str; 2] = ["async", "def"];
+
+/// the return type of a definition, a `-> T` after the args wins over the
+/// words in front of the name
+///
+/// `def area(self) -> float:` -> `float`, `int getAge() {` -> `int`
+fn return_type(type_: &str, after_args: &str) -> String {
+    if let Some(arrow) = after_args.find("->") {
+        return after_args[arrow + 2..]
+            .trim_end_matches(|c: char| c == ':' || c == '{' || c.is_whitespace())
+            .trim()
+            .to_string();
+    }
+    type_
+        .split_whitespace()
+        .filter(|w| !FUNCTION_KEYWORDS.contains(w))
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// statements that are written like a method, `while (i < n) {`
+const CONTROL_KEYWORDS: [&str; 9] = [
+    "if",
+    "for",
+    "while",
+    "switch",
+    "catch",
+    "with",
+    "function",
+    "try",
+    "synchronized",
+];
+
+/// java and c# words followed by a name like a type is, `return count;`
+const STATEMENT_KEYWORDS: [&str; 10] = [
+    "return",
+    "throw",
+    "new",
+    "else",
+    "yield",
+    "assert",
+    "continue",
+    "await",
+    "using",
+    "namespace",
+];
+
+/// python words that can start a line like `name: ..` or `name = ..` does
+const PYTHON_KEYWORDS: [&str; 8] = [
+    "else", "try", "finally", "lambda", "except", "with", "return", "pass",
+];
+
+/// type of a value when it can be told from the value alone
+///
+/// `Dog("rex")`, `Dog::new()`, `Dog { .. }` and `new Dog()` -> `Dog`, `[1, 2]` -> `list`,
+/// `3.5` -> `float`, `f64` or `number`, a call to a function or a plain name is empty
+fn value_type(value: &str, language: Option<&str>) -> String {
+    let value = value.trim();
+    let rust = language == Some("rs");
+    match language {
+        Some("js" | "ts") => return script_value_type(value),
+        Some("java" | "kt") => return jvm_value_type(value, language == Some("kt")),
+        Some("cs") => return csharp_value_type(value),
+        Some("go") => return go_value_type(value),
+        _ => {}
+    }
+    // `f"..."`, `b'...'`
+    let prefix_len = value
+        .find(|c: char| c == '"' || c == '\'')
+        .filter(|q| *q <= 2 && value[..*q].chars().all(|c| "rbfuRBFU".contains(c)));
+    let type_ = match value.chars().next() {
+        Some('\'') if rust => "char",
+        _ if prefix_len.is_some() => {
+            if value[..prefix_len.unwrap()].contains(|c| c == 'b' || c == 'B') {
+                "bytes"
+            } else {
+                "str"
+            }
+        }
+        Some('[') if !rust => "list",
+        Some('{') if !rust => "dict",
+        Some('(') if !rust => "tuple",
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            // `5u8`, `1.0f32`
+            let suffix = value.trim_start_matches(|c: char| {
+                c.is_ascii_digit() || c == '.' || c == '_' || c == '-'
+            });
+            match (rust, value.contains('.')) {
+                (true, _) if !suffix.is_empty() => suffix,
+                (true, true) => "f64",
+                (true, false) => "i32",
+                (false, true) => "float",
+                (false, false) => "int",
+            }
+        }
+        _ if value == "True" || value == "False" || value == "true" || value == "false" => "bool",
+        _ if value == "None" => "None",
+        _ => {
+            // a class is called to build an instance, `models.Dog(..)` is a `Dog`,
+            // so is `Dog::new(..)`, `Dog { .. }` and `Dog(..)` in rust
+            let callee = value
+                .split(|c| c == '(' || c == '{')
+                .next()
+                .unwrap_or("")
+                .trim();
+            let path = callee
+                .split(|c| c == '.' || c == ':')
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<&str>>();
+            let is_type = |s: &&str| {
+                s.starts_with(|c: char| c.is_uppercase())
+                    && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+            };
+            let class_name = match path.as_slice() {
+                [.., last] if is_type(last) => Some(*last),
+                [.., owner, _] if rust && is_type(owner) => Some(*owner),
+                _ => None,
+            };
+            let is_built = callee.len() < value.len();
+            return match class_name {
+                // variants of `Option` and `Result` do not name the type
+                Some(name) if is_built && !(rust && ["Some", "Ok", "Err"].contains(&name)) => {
+                    name.to_string()
+                }
+                _ => String::new(),
+            };
+        }
+    };
+    type_.to_string()
+}
+
+/// `"a"` -> `string`, `[]` -> `Array`, `new Map<string, Dog>()` -> `Map`
+fn script_value_type(value: &str) -> String {
+    let type_ = match value.chars().next() {
+        Some('"' | '\'' | '`') => "string",
+        Some('[') => "Array",
+        Some('{') => "object",
+        Some(c) if c.is_ascii_digit() || c == '-' => match value.ends_with('n') {
+            true => "bigint",
+            false => "number",
+        },
+        _ if value == "true" || value == "false" => "boolean",
+        _ => {
+            let class_name = value
+                .strip_prefix("new ")
+                .map(|built| {
+                    built
+                        .split(|c: char| c == '(' || c == '<')
+                        .next()
+                        .unwrap_or("")
+                })
+                .and_then(|callee| callee.trim().rsplit('.').next());
+            return class_name.unwrap_or("").to_string();
+        }
+    };
+    type_.to_string()
+}
+
+/// `3L` -> `long` or `Long`, `new ArrayList<>()` and kotlin `Dog("rex")` -> the class
+fn jvm_value_type(value: &str, kotlin: bool) -> String {
+    let type_ = match value.chars().next() {
+        Some('"') => "String",
+        Some('\'') if kotlin => "Char",
+        Some('\'') => "char",
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            let float = value.contains('.');
+            match value.chars().last() {
+                Some('L' | 'l') if kotlin => "Long",
+                Some('L' | 'l') => "long",
+                Some('F' | 'f') if kotlin => "Float",
+                Some('F' | 'f') => "float",
+                _ if kotlin && float => "Double",
+                _ if kotlin => "Int",
+                _ if float => "double",
+                _ => "int",
+            }
+        }
+        _ if value == "true" || value == "false" => match kotlin {
+            true => "Boolean",
+            false => "boolean",
+        },
+        _ => {
+            // java builds with `new`, kotlin calls the class
+            let callee = match value.strip_prefix("new ") {
+                Some(built) => built,
+                None if kotlin => value,
+                None => return String::new(),
+            };
+            let is_call = callee.contains('(');
+            let class_name = callee
+                .split(|c: char| c == '(' || c == '<' || c == '{')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .rsplit('.')
+                .next()
+                .unwrap_or("");
+            return match is_call && class_name.starts_with(|c: char| c.is_uppercase()) {
+                true => class_name.to_string(),
+                false => String::new(),
+            };
+        }
+    };
+    type_.to_string()
+}
+
+/// `$"{a}"` -> `string`, `2.5m` -> `decimal`, `new List<Dog>()` -> `List`, the
+/// target typed `new()` is empty
+fn csharp_value_type(value: &str) -> String {
+    let type_ = match value
+        .trim_start_matches(|c| c == '@' || c == '$')
+        .chars()
+        .next()
+    {
+        Some('"') => "string",
+        Some('\'') => "char",
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            let lower = value.to_ascii_lowercase();
+            match lower.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_') {
+                _ if lower.starts_with("0x") || lower.starts_with("0b") => "int",
+                l if l.ends_with("ul") || l.ends_with("lu") => "ulong",
+                l if l.ends_with('u') => "uint",
+                l if l.ends_with('l') => "long",
+                l if l.ends_with('f') => "float",
+                l if l.ends_with('m') => "decimal",
+                l if l.ends_with('d') || l.contains('.') || l.contains('e') => "double",
+                _ => "int",
+            }
+        }
+        _ if value == "true" || value == "false" => "bool",
+        _ => {
+            let class_name = value
+                .strip_prefix("new ")
+                .map(|built| {
+                    built
+                        .split(|c: char| c == '(' || c == '<' || c == '{' || c == '[')
+                        .next()
+                        .unwrap_or("")
+                })
+                .and_then(|callee| callee.trim().rsplit('.').next());
+            return class_name.unwrap_or("").to_string();
+        }
+    };
+    type_.to_string()
+}
+
+/// `&Dog{}`, `models.Dog{..}` and `new(Dog)` -> `Dog`, `2.5` -> `float64`, a call
+/// to a function is empty
+fn go_value_type(value: &str) -> String {
+    let type_ = match value.chars().next() {
+        Some('"' | '`') => "string",
+        Some('\'') => "rune",
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            let hex = value.starts_with("0x") || value.starts_with("0X");
+            match value.ends_with('i') {
+                true => "complex128",
+                false if !hex && value.contains(|c| c == '.' || c == 'e' || c == 'E') => "float64",
+                false => "int",
+            }
+        }
+        _ if value == "true" || value == "false" => "bool",
+        _ => {
+            if let Some(built) = value.strip_prefix("new(").or(value.strip_prefix("make(")) {
+                return go_type(split_top_level(built.trim_end_matches(')'), ',')[0]);
+            }
+            // a composite literal names its type before its `{`
+            return match value.find('{') {
+                Some(open) if !value[..open].contains('(') => go_type(&value[..open]),
+                _ => String::new(),
+            };
+        }
+    };
+    type_.to_string()
+}
+
+/// the type a go type expression is about, `*Repo[K, V]` -> `Repo`,
+/// `[]*models.Dog` -> `Dog`, the receiver `r *Repo` -> `Repo` and the results
+/// `(n int, err error)` -> `int`
+fn go_type(text: &str) -> String {
+    let text = text.trim();
+    let text = match text.strip_prefix('(') {
+        Some(results) => split_top_level(results.trim_end_matches(')'), ',')[0],
+        None => text,
+    };
+    // a named result or parameter comes before its type
+    let mut type_ = split_top_level(text.trim(), ' ')
+        .into_iter()
+        .filter(|t| !t.is_empty())
+        .last()
+        .unwrap_or("");
+    loop {
+        type_ = type_.trim_start_matches(|c| c == '*' || c == '&' || c == '.');
+        match type_.strip_prefix('[') {
+            Some(rest) => type_ = rest.split_once(']').map_or("", |(_, t)| t),
+            None => break,
+        }
+    }
+    let name = type_.split('[').next().unwrap_or("");
+    name.rsplit('.').next().unwrap_or("").to_string()
+}
+
+/// the types a go struct or interface embeds, the lines of its body that are
+/// only a type, `*Animal` or `io.Reader`, a pointer keeps its `*`
+fn embedded_types(body: &str) -> Vec<String> {
+    let embedded_regex =
+        Regex::new(r"(?m)^[ \t]*(\*?)(?:\w+\.)?(\w+)(?:\[[^\]\n]*\])?[ \t]*(?:`[^`\n]*`)?[ \t]*$")
+            .unwrap();
+    embedded_regex
+        .captures_iter(body)
+        .map(|caps| format!("{}{}", &caps[1], &caps[2]))
+        .collect()
+}
+
+/// access of the bases listed without one, only c++ has private inheritance
+fn default_base_access(file_path: &str, kind: ClassKind) -> Access {
+    match get_language_key(file_path).as_deref() {
+        Some("c" | "cpp") => kind.default_access(),
+        _ => Access::Public,
+    }
+}
+
+/// `(start, end)` of every indented block, from the `:` that opens it to the
+/// end of its last line
+///
+/// a line ending with a `:` outside of brackets opens a block, the block holds
+/// the lines after it that are indented deeper, blank lines do not end it
+fn indentation_blocks(code_text: &str) -> Vec<(usize, usize)> {
+    // (indent of the statement, offset of the `:`) of the blocks still open
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut blocks = Vec::new();
+    let mut depth = 0i32;
+    let mut statement_indent = 0;
+    let mut last_line_end = 0;
+    let mut line_start = 0;
+    for line in code_text.split_inclusive('\n') {
+        let content = line.trim_end();
+        let line_end = line_start + content.len();
+        if !content.trim_start().is_empty() {
+            // lines continuing the brackets of a statement keep its indentation
+            if depth <= 0 {
+                statement_indent = content.len() - content.trim_start().len();
+                while let Some(&(block_indent, colon)) = open.last() {
+                    if statement_indent > block_indent {
+                        break;
+                    }
+                    blocks.push((colon, last_line_end));
+                    open.pop();
+                }
+            }
+            depth = content.chars().fold(depth, |d, c| match c {
+                '(' | '[' | '{' => d + 1,
+                ')' | ']' | '}' => d - 1,
+                _ => d,
+            });
+            // `def f(a,\n      b):` is opened by its last line
+            if depth <= 0 && content.ends_with(':') {
+                open.push((statement_indent, line_end - 1));
+            }
+            last_line_end = line_end;
+        }
+        line_start += line.len();
+    }
+    while let Some((_, colon)) = open.pop() {
+        blocks.push((colon, last_line_end));
+    }
+    // a `:` with no deeper line after it opens nothing
+    blocks.retain(|(colon, end)| *end > colon + 1);
+    blocks.sort();
+    blocks
+}
+
+/// `public Alive, virtual Base` -> `[(Public, "Alive"), (Private, "Base")]` for a class
+///
+/// a base listed without the access gets `default_access`, python keyword
+/// arguments like `metaclass=ABCMeta` are no bases, the bounds of a rust trait
+/// are split at `+` and its lifetimes are left out, the constructor call of a
+/// kotlin base `Animal(name)` and its delegate `Pet by pet` too
+fn extract_parents(parents_str: &str, default_access: Access) -> Vec<(Access, String)> {
+    split_top_level(parents_str, ',')
+        .into_iter()
+        .flat_map(|base| split_top_level(base, '+'))
+        .map(|base| {
+            let base = base.split(" by ").next().unwrap_or(base);
+            match base.find('(') {
+                Some(open) if base.trim_end().ends_with(')') => &base[..open],
+                _ => base,
+            }
+        })
+        .filter(|base| !base.contains('=') && !base.trim_start().starts_with('\''))
+        .filter_map(|base| {
+            let mut access = default_access;
+            let mut words = Vec::new();
+            for w in base.split_whitespace() {
+                match Access::from_keyword(w) {
+                    Some(a) => access = a,
+                    None if w == "virtual" => {}
+                    None => words.push(w),
+                }
+            }
+            if words.is_empty() {
+                return None;
+            }
+            Some((access, words.join(" ")))
+        })
+        .collect()
+}
+
+/// `(start, end)` of the file scoped `namespace Zoo;`, from its `;` to the end of the file
+fn file_scoped_namespace(code_text: &str) -> Vec<(usize, usize)> {
+    let namespace_regex = Regex::new(r"(?m)^\s*namespace\s+[\w.]+\s*;").unwrap();
+    namespace_regex
+        .find(code_text)
+        .map(|m| vec![(m.end() - 1, code_text.len())])
+        .unwrap_or_default()
+}
+
+/// the text in front of a c# `=>` ends with the type or the name of a member,
+/// in front of a lambda it ends with `(`, `,`, `=` or `return`
+fn is_member_arrow(before: &str) -> bool {
+    let before = before.trim_end();
+    match before.chars().last() {
+        Some('>' | ']') => !before.ends_with("=>"),
+        Some(c) if c.is_alphanumeric() || c == '_' => {
+            let word = before
+                .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+                .unwrap_or("");
+            !STATEMENT_KEYWORDS.contains(&word)
+        }
+        _ => false,
+    }
+}
+
+/// `Name` of `} Name;`, where `scope_end` is the offset of the `}`
+fn name_after_scope(code_text: &str, scope_end: usize) -> Option<String> {
+    let after = code_text.get(scope_end + 1..)?.trim_start();
+    let name = after
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()?;
+    if name.is_empty() {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// `(start, end)` of every `template<..>` clause, from the `<` to the `>`
+fn template_clauses(code_text: &str) -> Vec<(usize, usize)> {
+    let template_regex = Regex::new(r"\btemplate\s*<").unwrap();
+    template_regex
+        .find_iter(code_text)
+        .filter_map(|m| {
+            let open = m.end() - 1;
+            matching_angle(code_text, open).map(|close| (open, close))
+        })
+        .collect()
+}
+
+/// length of a leading `public:` label and the spaces around it
+fn access_label_len(def: &str) -> usize {
+    let label_regex = Regex::new(r"^\s*(?:public|protected|private)\s*:\s*").unwrap();
+    label_regex.find(def).map_or(0, |l| l.end())
+}
+
+/// start of a match without the white space the regex took in front of it
+fn trimmed_start(m: &regex::Match) -> usize {
+    m.start() + m.as_str().len() - m.as_str().trim_start().len()
+}
+
+/// innermost scope that contains `start_pos`
 fn find_parent(start_pos: &usize, scope_entries: &Vec<SCOPE>) -> usize {
     let mut parent_scope = 0;
     for (s_i, s) in scope_entries.iter().enumerate() {
-        if *start_pos > s.0 && s.0 > scope_entries[parent_scope].0 {
+        if *start_pos > s.0 && *start_pos < s.1 && s.0 > scope_entries[parent_scope].0 {
             parent_scope = s_i;
         }
     }
@@ -645,8 +1500,13 @@ fn find_parent(start_pos: &usize, scope_entries: &Vec<SCOPE>) -> usize {
 /////////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn create_scope_availability(
-    project_path: &String,
     all_files: &Vec<&String>,
+    sources: &SourceFiles,
+    // file -> [files]
+    imported_files: &HashMap<usize, Vec<usize>>,
+    imported_names: &ImportedNames,
+    // file -> [files], through the chains of headers
+    visible_files: &HashMap<usize, Vec<usize>>,
     files_data: &Vec<(
         Vec<SCOPE>,
         Vec<CHILDACCESS>,
@@ -662,8 +1522,6 @@ pub fn create_scope_availability(
     HashMap<usize, HashMap<usize, Vec<(usize, usize)>>>,
     HashMap<usize, HashMap<usize, HashMap<String, StatefulClassConnection>>>,
 ) {
-    // file -> [files]
-    let imported_files: HashMap<usize, Vec<usize>> = read_all_imports(project_path, all_files);
     // file -> scope -> query -> class_connection
     let mut scoped_connectable_s: HashMap<
         usize,
@@ -672,8 +1530,10 @@ pub fn create_scope_availability(
     // file -> scope -> [(file, scope)]
     // TODO: This needs to be fixed
     let mut accessible_scopes: HashMap<usize, HashMap<usize, Vec<(usize, usize)>>> = HashMap::new();
-    // file -> [(class_name, scope)]
+    // file -> [(qualified class_name, scope)]
     let mut custom_classes: HashMap<usize, Vec<(String, usize)>> = HashMap::new();
+    // file -> scope -> qualified name of the classes and namespaces around it
+    let mut scope_paths: HashMap<usize, Vec<String>> = HashMap::new();
 
     let mut scope_parents: HashMap<usize, HashMap<usize, Vec<(usize, usize)>>> = HashMap::new();
 
@@ -682,13 +1542,14 @@ pub fn create_scope_availability(
         (scopes, child_access, equations, classes, functions, function_calls, lambdas, objects),
     ) in files_data.iter().enumerate()
     {
+        let paths = get_scope_paths(scopes, classes);
         for c in classes {
             let c_scope = c.0;
             let c_p_scope = scopes[c_scope].2;
             custom_classes
                 .entry(file)
                 .or_insert_with(Vec::new)
-                .push((c.1.clone(), c.0));
+                .push((join(&paths[c_p_scope], &c.1), c.0));
 
             scoped_connectable_s
                 .entry(file)
@@ -767,7 +1628,7 @@ pub fn create_scope_availability(
                     .push((file, c));
                 c = scopes.get(c).unwrap().2;
             }
-            for i in imported_files.get(&file).unwrap_or(&vec![]) {
+            for i in visible_files.get(&file).unwrap_or(&vec![]) {
                 accessible_scopes
                     .entry(file)
                     .or_insert_with(HashMap::new)
@@ -776,6 +1637,7 @@ pub fn create_scope_availability(
                     .push((i.clone(), 0));
             }
         }
+        scope_paths.insert(file, paths);
     }
     for (file, scopes) in scope_parents {
         for (s, sp) in scopes {
@@ -793,32 +1655,51 @@ pub fn create_scope_availability(
     // TODO: evaluate (scopes) and [scopes]
     // for (file, (scopes, access, equation, _, _, _, _, _)) in files_data.iter().enumerate() {}
 
+    // `from models import User` is the `User` class of `models.py`, whatever else is visible
+    let imported_classes = symbol_imports(imported_names, |file, name| {
+        custom_classes
+            .get(&file)?
+            .iter()
+            .position(|(class_name, _)| simple_name(class_name) == name)
+    });
+
     let mut temp_class_connections: HashMap<(usize, usize, String), StatefulClassConnection> =
         HashMap::new();
     for (file, (scopes, _, _, _, _, _, _, _)) in files_data.iter().enumerate() {
         let lang_data_types = get_data_types(all_files[file]).unwrap();
+        let usings = read_using_directives(all_files[file], sources);
         for (s, _) in scopes.iter().enumerate() {
-            if let Some(scope_queries) = scoped_connectable_s.get(&file).unwrap().get(&s) {
+            // a file without any declaration has no connectables
+            if let Some(scope_queries) = scoped_connectable_s.get(&file).and_then(|c| c.get(&s)) {
                 for q in scope_queries.keys() {
-                    for (access_f, access_s) in
-                        accessible_scopes.get(&file).unwrap().get(&s).unwrap()
-                    {
+                    let q_name = match scope_queries.get(q) {
+                        // `Box<Animal>` is looked up as `Box`
+                        Some(StatefulClassConnection::Undiscovered(q_name)) => base_name(q_name),
+                        _ => continue,
+                    };
+                    let imported = imported_classes
+                        .get(&file)
+                        .and_then(|i| i.get(&q_name))
+                        .map(|(i_file, i_class)| (*i_file, &custom_classes[i_file][*i_class]));
+                    let candidates = lookup_candidates(&scope_paths[&file][s], &q_name, &usings);
+                    let no_access = vec![];
+                    let scope_access = accessible_scopes
+                        .get(&file)
+                        .and_then(|a| a.get(&s))
+                        .unwrap_or(&no_access);
+                    for (access_f, _) in scope_access {
                         let temp = vec![];
                         let access_classes = custom_classes.get(access_f).unwrap_or(&temp);
-                        if let Some(StatefulClassConnection::Undiscovered(q_name)) =
-                            scope_queries.get(q)
-                        {
-                            if let Some(connection) = get_connected_class(
-                                lang_data_types,
-                                &accessible_scopes,
-                                &scoped_connectable_s,
-                                access_f,
-                                access_s,
-                                q_name,
-                                access_classes,
-                            ) {
-                                temp_class_connections.insert((file, s, q.clone()), connection);
-                            }
+                        if let Some(connection) = get_connected_class(
+                            &lang_data_types,
+                            access_f,
+                            &q_name,
+                            &candidates,
+                            access_classes,
+                            imported,
+                        ) {
+                            temp_class_connections.insert((file, s, q.clone()), connection);
+                            break;
                         }
                     }
                 }
@@ -848,7 +1729,7 @@ pub fn create_scope_availability(
         for eq in equations {}
     }
 
-    log_hashmap("imported files", &imported_files);
+    log_hashmap("imported files", imported_files);
     log_nested_hashmap("accessible scopes", &accessible_scopes);
     log_deeply_nested_hashmap("scoped connectable(s)", &scoped_connectable_s);
 
@@ -867,42 +1748,60 @@ fn find_next_scope(pos: &usize, scopes: &Vec<SCOPE>) -> usize {
     return res;
 }
 
+/// qualified name of the classes and namespaces around every scope of a file
+fn get_scope_paths(scopes: &[SCOPE], classes: &[CLASS]) -> Vec<String> {
+    let scope_classes = classes
+        .iter()
+        .map(|c| (c.0, &c.1))
+        .collect::<HashMap<usize, &String>>();
+    let mut paths: Vec<String> = Vec::with_capacity(scopes.len());
+    for (s_i, s) in scopes.iter().enumerate() {
+        // a parent always comes before its children
+        let parent_path = match paths.get(s.2) {
+            Some(p) => p.clone(),
+            None => String::new(),
+        };
+        paths.push(match scope_classes.get(&s_i) {
+            Some(name) => join(&parent_path, name),
+            None => parent_path,
+        });
+    }
+    paths
+}
+
+/// `candidates` are the qualified names the query may mean, most likely first,
+/// `imported` is the class the query was imported as by name, it wins over them
 fn get_connected_class(
-    data_types: &&[&str],
-    accessible_scopes: &HashMap<usize, HashMap<usize, Vec<(usize, usize)>>>,
-    scoped_connectable_s: &HashMap<usize, HashMap<usize, HashMap<String, StatefulClassConnection>>>,
+    data_types: &[String],
     file: &usize,
-    scope: &usize,
     query: &String,
+    candidates: &[String],
     file_classes: &Vec<(String, usize)>,
+    imported: Option<(usize, &(String, usize))>,
 ) -> Option<StatefulClassConnection> {
     for (i, dt) in data_types.iter().enumerate() {
-        if *query == **dt {
+        if query == dt {
             return Some(StatefulClassConnection::DataType(i, dt.to_string()));
         }
     }
-    if let Some(class_pos) =
-        file_classes
-            .iter()
-            .find_map(|x| if x.0 == *query { Some(x) } else { None })
-    {
-        for (access_f, access_s) in accessible_scopes.get(file).unwrap().get(scope).unwrap() {
-            if let Some(file_queries) = scoped_connectable_s.get(access_f) {
-                if let Some(scope_queries) = file_queries.get(access_s) {
-                    for q in scope_queries.keys() {
-                        if q == query {
-                            return Some(StatefulClassConnection::Connected(
-                                access_f.clone(),
-                                class_pos.1,
-                                query.clone(),
-                            ));
-                        }
-                    }
-                }
-            }
-        }
+    if let Some((i_file, (i_name, i_scope))) = imported {
+        return Some(StatefulClassConnection::Connected(
+            i_file,
+            *i_scope,
+            i_name.clone(),
+        ));
     }
-    None
+    let names = file_classes
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>();
+    resolve(candidates, &names, |_| true).map(|c_i| {
+        StatefulClassConnection::Connected(
+            file.clone(),
+            file_classes[c_i].1,
+            file_classes[c_i].0.clone(),
+        )
+    })
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////////
@@ -1015,7 +1914,7 @@ impl fmt::Display for SCOPE {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
                 f,
-                "----------------------------------------------------\n\t{3}{0}<-->{1}{4}\n\t||parent:{2} {5}",
+                "----------------------------------------------------\n\t{3}{0}<-->{1}{4} {6}\n\t||parent:{2} {5}",
                 self.0, self.1, self.2, match self.3 {
                     0 => '(',
                     1 => '{',
@@ -1032,7 +1931,8 @@ impl fmt::Display for SCOPE {
                 match self.3 {
                     0 => self.4.clone(),
                     _ => "".to_string()
-                }
+                },
+                self.5
             )
     }
 }
@@ -1061,8 +1961,8 @@ impl fmt::Display for CLASS {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "----------------------------------------------------\n\t{1} : {2:?}\n\t||scope:{0:?}",
-            self.0, self.1, self.2
+            "----------------------------------------------------\n\t{4:?} {1} : {2:?}\n\t||scope:{0:?} {3}",
+            self.0, self.1, self.2, self.3, self.4
         )
     }
 }
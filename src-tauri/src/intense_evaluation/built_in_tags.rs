@@ -0,0 +1,543 @@
+use regex::Regex;
+use std::{collections::HashMap, fs, io, path::Path};
+
+use super::{language_file_intense_extract, CLASS, FUNCTION, OBJECT, SCOPE};
+use crate::data::{get_language_key, get_language_name, get_regex_class};
+use crate::tag_entry::{
+    file_filter::{FileFilter, ScanOptions},
+    Access, ClassEntry, ClassKind, FunctionEntry, ObjectEntry, ScopeEntry, TagEntry, ACCESS_LABEL,
+};
+use crate::{
+    code_lexer::{read_sources, SourceFile, SourceFiles},
+    qualified_names::{parent_path, qualify_hard_data, simple_name},
+    span::LineIndex,
+    type_ref::split_top_level,
+};
+
+/// directories that only hold dependencies or build output
+const SKIPPED_DIRS: [&str; 2] = ["node_modules", "target"];
+
+/// walks the project and builds the tags and the hard data of every supported
+/// file with the `data::regex_*` tables, no tags file or ctags is needed
+///
+/// returns the scanned files, their text and code, their tags and their hard data
+pub fn extract_project_tags(
+    project_path: &String,
+    options: &ScanOptions,
+) -> Result<
+    (
+        Vec<String>,
+        SourceFiles,
+        Vec<TagEntry>,
+        HashMap<
+            String,
+            (
+                Vec<ScopeEntry>,
+                Vec<ClassEntry>,
+                Vec<FunctionEntry>,
+                Vec<ObjectEntry>,
+            ),
+        >,
+    ),
+    String,
+> {
+    let filter = FileFilter::new(&options.include, &options.exclude)?;
+
+    let mut files = Vec::new();
+    walk_dir(Path::new(project_path), &mut files)
+        .map_err(|e| format!("couldn't walk the project {} : {}", project_path, e))?;
+    files.retain(|f| {
+        is_scanned_language(f, &options.languages) && filter.is_included(project_path, f)
+    });
+    files.sort();
+
+    let sources = read_sources(&files.iter().collect::<Vec<&String>>());
+    let mut tags = Vec::new();
+    let mut hard_data = HashMap::new();
+    for (file_i, file) in files.iter().enumerate() {
+        let source = match sources.get(file) {
+            Some(source) => source,
+            None => continue,
+        };
+        if let Some((scopes, _, _, classes, functions, _, _, objects)) =
+            language_file_intense_extract(file_i, file, source)
+        {
+            let (file_tags, file_hard_data) =
+                to_hard_data(file, source, &scopes, &classes, &functions, &objects);
+            tags.extend(file_tags);
+            hard_data.insert(file.clone(), file_hard_data);
+        }
+    }
+
+    Ok((files, sources, tags, hard_data))
+}
+
+/// only a folder that can not be read fails, an entry of it that can not be
+/// read is reported and skipped
+fn walk_dir(dir: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("skipped an entry of {} : {}", dir.display(), e);
+                continue;
+            }
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        // symlinks are not followed, they can loop
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => {
+                eprintln!("skipped {} : {}", entry.path().display(), e);
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            if let Err(e) = walk_dir(&entry.path(), files) {
+                eprintln!("skipped the folder {} : {}", entry.path().display(), e);
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path().to_string_lossy().to_string());
+        }
+    }
+    Ok(())
+}
+
+fn is_scanned_language(file_path: &String, languages: &[String]) -> bool {
+    let key = match get_language_key(file_path) {
+        // a language only read for its imports has nothing to extract
+        Some(k) if get_regex_class(file_path).1.is_some() => k,
+        _ => return false,
+    };
+    languages.is_empty()
+        || languages.iter().any(|l| {
+            l.eq_ignore_ascii_case(&key)
+                || get_language_name(&key).map_or(false, |n| l.eq_ignore_ascii_case(&n))
+        })
+}
+
+/// turns the intense data of one file into what `brackets_walk` would have
+/// produced from a tags file
+///
+/// only `{}` scopes, the `()` of rust tuple structs and the `=> (..)` bodies of
+/// arrow functions are kept, the other `()` and `[]` scopes are folded into
+/// their closest curly parent
+fn to_hard_data(
+    file_path: &str,
+    source: &SourceFile,
+    scopes: &[SCOPE],
+    classes: &Vec<CLASS>,
+    functions: &Vec<FUNCTION>,
+    objects: &Vec<OBJECT>,
+) -> (
+    Vec<TagEntry>,
+    (
+        Vec<ScopeEntry>,
+        Vec<ClassEntry>,
+        Vec<FunctionEntry>,
+        Vec<ObjectEntry>,
+    ),
+) {
+    let file_text = &source.text;
+    let lines = file_text.lines().collect::<Vec<&str>>();
+    let line_text = |line: usize| -> String {
+        lines
+            .get(line - 1)
+            .map(|l| l.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let code_text = &source.code;
+    let positions = LineIndex::new(file_text);
+
+    // intense scope -> hard scope
+    let mut scope_map: HashMap<usize, usize> = HashMap::new();
+    let mut scope_entries: Vec<ScopeEntry> = Vec::new();
+    scope_entries.push(ScopeEntry {
+        file_name: file_path.to_string(),
+        span: scopes[0].5,
+        parent_scope: u128::MAX,
+        children_scop: Vec::new(),
+    });
+    scope_map.insert(0, 0);
+
+    for (s_i, s) in scopes.iter().enumerate().skip(1) {
+        if s.3 != 1 && !classes.iter().any(|c| c.0 == s_i) && !functions.iter().any(|f| f.0 == s_i)
+        {
+            continue;
+        }
+        let parent = curly_parent(s_i, scopes, &scope_map);
+        scope_entries.push(ScopeEntry {
+            file_name: file_path.to_string(),
+            span: s.5,
+            parent_scope: parent as u128,
+            children_scop: Vec::new(),
+        });
+        let new_scope_idx = scope_entries.len() - 1;
+        scope_entries[parent]
+            .children_scop
+            .push(new_scope_idx as u128);
+        scope_map.insert(s_i, new_scope_idx);
+    }
+
+    // name and kind of the class, struct, enum or namespace a scope belongs to
+    let owner_of = |class_entries: &Vec<ClassEntry>, scope: usize| -> Option<(String, ClassKind)> {
+        class_entries
+            .iter()
+            .find(|c| c.class_scope == scope)
+            .map(|c| (c.name.clone(), c.kind))
+    };
+    let language = get_language_key(file_path);
+    let access_at = |class_entries: &Vec<ClassEntry>, scope: usize, offset: usize, name: &str| {
+        let class = class_entries.iter().find(|c| c.class_scope == scope);
+        match language.as_deref() {
+            // python has no labels, `_name` is protected and `__name` private by convention
+            Some("py") => return class.map(|_| python_access(name)),
+            Some("rs") => return class.map(|c| rust_access(&code_text, c, offset)),
+            Some("go") => return class.map(|_| go_access(name)),
+            Some("js" | "ts" | "kt") => {
+                return class.map(|_| modifier_access(&code_text, offset, name, Access::Public))
+            }
+            // a java member without a modifier is seen by its package, like `pub(crate)`
+            Some("java") => {
+                return class.map(|c| {
+                    let default = match c.kind {
+                        ClassKind::Interface => Access::Public,
+                        _ => Access::Protected,
+                    };
+                    modifier_access(&code_text, offset, name, default)
+                })
+            }
+            // a c# member is private unless it says otherwise, `internal` is seen by its assembly
+            Some("cs") => {
+                return class.map(|c| {
+                    let default = match c.kind {
+                        ClassKind::Interface => Access::Public,
+                        _ => Access::Private,
+                    };
+                    modifier_access(&code_text, offset, name, default)
+                })
+            }
+            _ => {}
+        }
+        member_access(
+            &code_text,
+            class_entries.iter().find(|c| c.class_scope == scope),
+            &scope_entries[scope],
+            offset,
+        )
+    };
+
+    let mut tags = Vec::new();
+    let mut class_entries = Vec::new();
+    for c in classes {
+        let class_scope = match scope_map.get(&c.0) {
+            Some(s) => *s,
+            None => continue,
+        };
+        let parent_scope = scope_entries[class_scope].parent_scope as usize;
+        let owner = owner_of(&class_entries, parent_scope);
+        let line = c.3.start_line;
+        let parents = c.2.clone();
+        tags.push(TagEntry {
+            tag_name: c.1.clone(),
+            file_name: file_path.to_string(),
+            reg_ex: line_text(line),
+            tag: c.4.tag().to_string(),
+            line: Some(line),
+            scope_kind: owner.as_ref().map(|(_, k)| k.name().to_string()),
+            scope: owner.map(|(n, _)| n),
+            inherits: parents.iter().map(|(_, p)| p.clone()).collect(),
+            template: template_field(&c.5),
+            ..Default::default()
+        });
+        class_entries.push(ClassEntry {
+            name: c.1.clone(),
+            qualified_name: c.1.clone(),
+            kind: c.4,
+            parent_scope,
+            class_scope,
+            template_params: c.5.clone(),
+            parents,
+            span: c.3,
+            partial: c.6,
+        });
+    }
+
+    let mut function_entries = Vec::new();
+    for f in functions {
+        let function_scope = match scope_map.get(&f.0) {
+            Some(s) => *s,
+            None => continue,
+        };
+        let parent_scope = scope_entries[function_scope].parent_scope as usize;
+        let owner = owner_of(&class_entries, parent_scope);
+        let access = match language.as_deref() {
+            // a go method is written apart from its type, its name tells if it is exported
+            Some("go") if owner.is_none() && !parent_path(&f.1).is_empty() => {
+                Some(go_access(simple_name(&f.1)))
+            }
+            _ => access_at(&class_entries, parent_scope, f.5.start, simple_name(&f.1)),
+        };
+        let line = f.5.start_line;
+        // `Mammal::getAge` is `getAge` defined out of line for `Mammal`
+        let name = simple_name(&f.1).to_string();
+        let qualifier = Some(parent_path(&f.1).to_string()).filter(|q| !q.is_empty());
+        tags.push(TagEntry {
+            tag_name: name.clone(),
+            file_name: file_path.to_string(),
+            reg_ex: line_text(line),
+            tag: "f".to_string(),
+            line: Some(line),
+            // like ctags, the class of an out of line definition is its scope
+            scope_kind: match (&owner, &qualifier) {
+                (Some((_, k)), _) => Some(k.name().to_string()),
+                (None, Some(_)) => Some(ClassKind::Class.name().to_string()),
+                (None, None) => None,
+            },
+            scope: owner.as_ref().map(|(n, _)| n.clone()).or(qualifier.clone()),
+            typeref: Some(format!("typename:{}", f.2.trim())),
+            access: access.map(|a| a.name().to_string()),
+            template: template_field(&f.6),
+            ..Default::default()
+        });
+        function_entries.push(FunctionEntry {
+            qualified_name: name.clone(),
+            name,
+            parent_scope,
+            function_scope,
+            template_params: f.6.clone(),
+            class_name: f.2.trim().to_string(),
+            owner: owner.map(|(n, _)| n),
+            qualifier,
+            access,
+            span: f.5,
+            signature: f.7.clone(),
+            pointer_receiver: f.8,
+        });
+    }
+
+    let mut object_entries = Vec::new();
+    for o in objects {
+        // objects found inside `()` or `[]` are arguments or indices, not members
+        let parent_scope = match scope_map.get(&o.0) {
+            Some(s) => *s,
+            None => continue,
+        };
+        let owner = owner_of(&class_entries, parent_scope);
+        let access = access_at(&class_entries, parent_scope, o.3.start, &o.1);
+        let line = o.3.start_line;
+        // variables of a namespace are not members
+        let is_member = match &owner {
+            Some((_, kind)) => *kind != ClassKind::Namespace,
+            None => false,
+        };
+        tags.push(TagEntry {
+            tag_name: o.1.clone(),
+            file_name: file_path.to_string(),
+            reg_ex: line_text(line),
+            tag: if is_member { "m" } else { "v" }.to_string(),
+            line: Some(line),
+            scope_kind: owner.as_ref().map(|(_, k)| k.name().to_string()),
+            scope: owner.as_ref().map(|(n, _)| n.clone()),
+            typeref: Some(format!("typename:{}", o.2)),
+            access: access.map(|a| a.name().to_string()),
+            ..Default::default()
+        });
+        object_entries.push(ObjectEntry {
+            name: o.1.clone(),
+            qualified_name: o.1.clone(),
+            parent_scope,
+            class_name: o.2.clone(),
+            owner: owner.map(|(n, _)| n),
+            access,
+            span: o.3,
+        });
+    }
+
+    // the enumerators are the objects of an enum, typed by it
+    for c in classes.iter().filter(|c| c.4 == ClassKind::Enum) {
+        let parent_scope = match scope_map.get(&c.0) {
+            Some(s) => *s,
+            None => continue,
+        };
+        for (start, name) in enumerators(&code_text, &scopes[c.0]) {
+            let span = positions.span(start, start + name.len());
+            tags.push(TagEntry {
+                tag_name: name.clone(),
+                file_name: file_path.to_string(),
+                reg_ex: line_text(span.start_line),
+                tag: "e".to_string(),
+                line: Some(span.start_line),
+                scope_kind: Some(ClassKind::Enum.name().to_string()),
+                scope: Some(c.1.clone()),
+                ..Default::default()
+            });
+            object_entries.push(ObjectEntry {
+                qualified_name: name.clone(),
+                name,
+                parent_scope,
+                class_name: c.1.clone(),
+                owner: Some(c.1.clone()),
+                access: Some(Access::Public),
+                span,
+            });
+        }
+    }
+
+    qualify_hard_data(
+        &scope_entries,
+        &mut class_entries,
+        &mut function_entries,
+        &mut object_entries,
+    );
+
+    (
+        tags,
+        (
+            scope_entries,
+            class_entries,
+            function_entries,
+            object_entries,
+        ),
+    )
+}
+
+/// `(offset, name)` of every enumerator in the body of an enum
+///
+/// `{ RED = 1, GREEN, BLUE }` -> `RED`, `GREEN`, `BLUE`, the members of a java
+/// enum follow its enumerators after a `;`
+fn enumerators(code_text: &str, enum_scope: &SCOPE) -> Vec<(usize, String)> {
+    let body_start = enum_scope.0 + 1;
+    let body = code_text.get(body_start..enum_scope.1).unwrap_or("");
+    let body = split_top_level(body, ';')[0];
+    let mut res = Vec::new();
+    let mut item_start = 0;
+    for item in body.split(',') {
+        let name_offset = item.len() - item.trim_start().len();
+        let name = item
+            .trim_start()
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .unwrap_or("");
+        if !name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit()) {
+            res.push((body_start + item_start + name_offset, name.to_string()));
+        }
+        item_start += item.len() + 1;
+    }
+    res
+}
+
+/// `[T, N]` -> `<T, N>`, only the names are known, not `typename` or `int`
+fn template_field(params: &[String]) -> Option<String> {
+    if params.is_empty() {
+        return None;
+    }
+    Some(format!("<{}>", params.join(", ")))
+}
+
+/// access of a member of `class` that starts at `offset`
+///
+/// the last `public:` / `private:` label of the class body before the member
+/// wins, labels inside nested bodies are skipped, none for a non member
+fn member_access(
+    code_text: &str,
+    class: Option<&ClassEntry>,
+    class_scope: &ScopeEntry,
+    offset: usize,
+) -> Option<Access> {
+    let kind = class?.kind;
+    if !matches!(kind, ClassKind::Class | ClassKind::Struct | ClassKind::Union) {
+        return None;
+    }
+    let body_start = class_scope.span.start + 1;
+    let body = code_text.get(body_start..offset).unwrap_or("");
+
+    let mut access = kind.default_access();
+    let mut depth = 0i32;
+    let mut walked = 0;
+    for caps in ACCESS_LABEL.captures_iter(body) {
+        let label = caps.get(1).unwrap();
+        for ch in body[walked..label.start()].chars() {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        walked = label.start();
+        if depth == 0 {
+            access = Access::from_keyword(label.as_str()).unwrap_or(access);
+        }
+    }
+    Some(access)
+}
+
+/// `__slots` is private, `_cache` protected, `name` and `__init__` public
+fn python_access(name: &str) -> Access {
+    if name.starts_with("__") && !name.ends_with("__") {
+        Access::Private
+    } else if name.starts_with('_') && !name.ends_with("__") {
+        Access::Protected
+    } else {
+        Access::Public
+    }
+}
+
+/// a go name starting with an upper case letter is exported, the others are seen
+/// by their package, like `pub(crate)`
+fn go_access(name: &str) -> Access {
+    match name.starts_with(|c: char| c.is_uppercase()) {
+        true => Access::Public,
+        false => Access::Protected,
+    }
+}
+
+/// `pub` members are public, `pub(crate)` ones protected and the others private,
+/// the items of a trait and of a trait implementation are all public
+fn rust_access(code_text: &str, class: &ClassEntry, offset: usize) -> Access {
+    let is_trait_item = class.kind == ClassKind::Interface
+        || (class.kind == ClassKind::Implementation && !class.parents.is_empty());
+    let definition = code_text.get(offset..).unwrap_or("");
+    if is_trait_item || definition.starts_with("pub ") {
+        Access::Public
+    } else if definition.starts_with("pub(") {
+        Access::Protected
+    } else {
+        Access::Private
+    }
+}
+
+/// the modifier written in front of the member, `private` and javascript
+/// `#name` members are private, `protected` and kotlin `internal` ones protected,
+/// `default` for a member without one
+fn modifier_access(code_text: &str, offset: usize, name: &str, default: Access) -> Access {
+    if name.starts_with('#') {
+        return Access::Private;
+    }
+    let definition = code_text.get(offset..).unwrap_or("");
+    let modifiers = &definition[..definition.find(name).unwrap_or(0)];
+    modifiers
+        .split_whitespace()
+        .find_map(|w| match w {
+            "public" => Some(Access::Public),
+            "private" => Some(Access::Private),
+            "protected" | "internal" => Some(Access::Protected),
+            _ => None,
+        })
+        .unwrap_or(default)
+}
+
+fn curly_parent(scope: usize, scopes: &[SCOPE], scope_map: &HashMap<usize, usize>) -> usize {
+    let mut p = scopes[scope].2;
+    while p != usize::MAX {
+        if let Some(hard_p) = scope_map.get(&p) {
+            return *hard_p;
+        }
+        p = scopes[p].2;
+    }
+    0
+}
@@ -1,11 +1,20 @@
 use serde_json::json;
 use serde::Deserialize;
-use std::{path::PathBuf, str};
+use std::{collections::HashMap, path::PathBuf, str};
 use tauri::Runtime;
 use tokio::time::{sleep, Duration};
 
+use project_data::{ProjectData, TagsSource};
+use tag_entry::file_filter::ScanOptions;
+
+mod code_lexer;
 mod data;
 mod project_data;
+mod qualified_names;
+mod source_modules;
+mod include_costs;
+mod span;
+mod type_ref;
 mod evaluate_imports;
 mod intense_evaluation;
 mod tag_entry;
@@ -40,15 +49,65 @@ async fn request_project_structure<R: Runtime>(
     let project_data = match project_data::get_project_data() {
         Some(data) => data,
         None => {
-            let new_data = project_data::create_project_data(
+            let new_data = match project_data::create_project_data(
                 project_path.clone(),
-                tags_path.clone(),
-            ).await;
+                TagsSource::TagsFile(tags_path.clone()),
+            ).await {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("couldn't create the project data due to \n\terror : {}", e);
+                    return;
+                }
+            };
             project_data::set_project_data(new_data.clone());
             new_data
         }
     };
 
+    emit_project_data(&project_data, &window);
+}
+
+/// same as `request_project_structure`, but ctags is run by the backend
+/// instead of reading a tags file
+#[tauri::command]
+async fn request_project_structure_with_ctags<R: Runtime>(
+    project_path: String,
+    ctags_options: Option<ScanOptions>,
+    window: tauri::Window<R>,
+) -> Result<(), String> {
+    project_data::clear_project_data();
+
+    let project_data = project_data::create_project_data(
+        project_path,
+        TagsSource::Ctags(ctags_options.unwrap_or_default()),
+    ).await?;
+    project_data::set_project_data(project_data.clone());
+
+    emit_project_data(&project_data, &window);
+    Ok(())
+}
+
+/// same as `request_project_structure`, but the symbols are found by the
+/// built in extractor, neither a tags file nor ctags is needed
+#[tauri::command]
+async fn request_project_structure_built_in<R: Runtime>(
+    project_path: String,
+    scan_options: Option<ScanOptions>,
+    window: tauri::Window<R>,
+) -> Result<(), String> {
+    project_data::clear_project_data();
+
+    let project_data = project_data::create_project_data(
+        project_path,
+        TagsSource::BuiltIn(scan_options.unwrap_or_default()),
+    ).await?;
+    project_data::set_project_data(project_data.clone());
+
+    emit_project_data(&project_data, &window);
+    Ok(())
+}
+
+fn emit_project_data<R: Runtime>(project_data: &ProjectData, window: &tauri::Window<R>) {
     // Convert data to JSON format
     let (imports_json, tags_json, children_json) = evaluate_imports::jsonify_evaluated_data(
         &project_data.raw_imports,
@@ -60,7 +119,11 @@ async fn request_project_structure<R: Runtime>(
         project_data.all_files,
         imports_json,
         tags_json,
-        children_json
+        children_json,
+        // packages from outside the project, shown next to the files importing them
+        project_data.external_dependencies,
+        // file -> the name it imports -> the `(file, tag)` the name stands for
+        project_data.symbol_imports
     ]);
 
     // Emit project structure
@@ -75,6 +138,32 @@ async fn request_project_structure<R: Runtime>(
         }
     }
 
+    // headers and their sources, the structure view can collapse each into one unit
+    if let Err(e) = window.emit("source_modules", &project_data.source_modules) {
+        eprintln!(
+            "couldn't emit the source modules properly due to \n\terror : {}",
+            e
+        );
+    }
+
+    // what the includes of every c / c++ file cost, the most expensive first
+    if let Err(e) = window.emit("include_costs", &project_data.include_costs) {
+        eprintln!(
+            "couldn't emit the include costs properly due to \n\terror : {}",
+            e
+        );
+    }
+
+    // entries of the tags file that were skipped
+    if !project_data.tags_diagnostics.is_empty() {
+        if let Err(e) = window.emit("tags_diagnostics", &project_data.tags_diagnostics) {
+            eprintln!(
+                "couldn't emit the tags diagnostics properly due to \n\terror : {}",
+                e
+            );
+        }
+    }
+
     println!("\n\n------ intense extract ------\n\n");
 
     // Emit intense data
@@ -94,6 +183,19 @@ async fn request_project_structure<R: Runtime>(
         }
     }
 }
+/// the children table of the loaded project without private and protected members
+#[tauri::command]
+async fn request_api_children() -> Result<HashMap<String, Vec<(String, String)>>, String> {
+    let project_data = project_data::get_project_data().ok_or("Project data not initialized")?;
+    let api_children =
+        evaluate_imports::api_children(&project_data.all_tags, &project_data.children_tags);
+    let (_, _, children_json) = evaluate_imports::jsonify_evaluated_data(
+        &project_data.raw_imports,
+        &project_data.all_tags,
+        &api_children,
+    );
+    Ok(children_json)
+}
 #[tauri::command]
 fn save_project_structure(_tags_path: &str) {}
 #[tauri::command]
@@ -229,6 +331,9 @@ fn main() {
     tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
             request_project_structure,
+            request_project_structure_with_ctags,
+            request_project_structure_built_in,
+            request_api_children,
             save_project_structure,
             del_project_structure,
             request_project_data_flow,
@@ -2,22 +2,39 @@ use serde::{Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
 use lazy_static::lazy_static;
+use crate::code_lexer::read_sources;
+use crate::data::load_language_specs;
 use crate::tag_entry;
-use crate::evaluate_imports::{self, ProgramTag};
+use crate::evaluate_imports::{self, ExternalDependency, ImportedNames, ProgramTag, SymbolImports};
 use crate::intense_evaluation;
 use crate::intense_evaluation::StatefulClassConnection;
-use crate::tag_entry::{TagEntry, ScopeEntry, ClassEntry, FunctionEntry, ObjectEntry};
+use crate::source_modules::{self, SourceModule};
+use crate::include_costs::{self, IncludeCost};
+use crate::tag_entry::{TagEntry, TagDiagnostic, ScopeEntry, ClassEntry, FunctionEntry, ObjectEntry};
+use crate::tag_entry::ctags_runner;
+use crate::tag_entry::file_filter::ScanOptions;
 // use serde_json::json;
 
 #[derive(Default, Serialize, Clone)]
 pub struct ProjectData {
 	pub project_path: String,
   pub tags_data: Vec<TagEntry>,
+  pub tags_diagnostics: Vec<TagDiagnostic>,
   pub all_files: Vec<String>,
   pub hard_data: HashMap<String, (Vec<ScopeEntry>, Vec<ClassEntry>, Vec<FunctionEntry>, Vec<ObjectEntry>)>,
   pub raw_imports: HashMap<usize, Vec<usize>>,
+  /// the names each file takes from the files it imports
+  pub imported_names: ImportedNames,
+  /// the tags the names each file imports by name stand for
+  pub symbol_imports: SymbolImports,
+  /// the packages imported from outside the project and the files importing them
+  pub external_dependencies: Vec<ExternalDependency>,
   pub all_tags: HashMap<usize, Vec<ProgramTag>>,
   pub children_tags: HashMap<(usize, usize), Vec<(usize, usize)>>,
+  /// headers grouped with the source files that implement them
+  pub source_modules: Vec<SourceModule>,
+  /// transitive includes and included lines of every c / c++ file
+  pub include_costs: Vec<IncludeCost>,
   pub custom_classes: HashMap<usize, Vec<(String, usize)>>,
   pub accessible_scopes: HashMap<usize, HashMap<usize, Vec<(usize, usize)>>>,
   pub scoped_connectables: HashMap<usize, HashMap<usize, HashMap<String, StatefulClassConnection>>>,
@@ -39,38 +56,88 @@ pub fn clear_project_data() {
     *PROJECT_DATA.lock().unwrap() = None;
 }
 
+/// where the tags of the project come from
+pub enum TagsSource {
+	/// a tags file produced by the user
+	TagsFile(String),
+	/// ctags is run by the backend over the project
+	Ctags(ScanOptions),
+	/// the project is walked and read with the built in `data::regex_*` tables
+	BuiltIn(ScanOptions),
+}
+
 pub async fn create_project_data(
 		project_path: String,
-		tags_path: String,
-) -> ProjectData {
+		tags_source: TagsSource,
+) -> Result<ProjectData, String> {
 
-	//
+	// languages described by the project are read along with the built in ones
+	for e in load_language_specs(&project_path) {
+		eprintln!("couldn't load the language spec {}", e);
+	}
 
-	let tags_result = match tag_entry::get_tags_data(tags_path) {
+	// the built in extractor produces the files and the hard data along with the tags
+	let mut built_in_data = None;
+	let (tags_result, tags_diagnostics) = match tags_source {
+		TagsSource::TagsFile(tags_path) => match tag_entry::get_tags_data(tags_path.clone()) {
 			Ok(res) => res,
-			Err(_) => Vec::new(),
+			Err(e) => {
+				eprintln!("couldn't read the tags file {} due to \n\terror : {}", tags_path, e);
+				(Vec::new(), Vec::new())
+			}
+		},
+		// ctags runs over the whole project, the async runtime is not held meanwhile
+		TagsSource::Ctags(options) => {
+			let path = project_path.clone();
+			tauri::async_runtime::spawn_blocking(move || ctags_runner::run_ctags(&path, &options))
+				.await
+				.map_err(|e| e.to_string())??
+		}
+		TagsSource::BuiltIn(options) => {
+			let (files, sources, tags, hard_data) =
+				intense_evaluation::extract_project_tags(&project_path, &options)?;
+			built_in_data = Some((files, sources, hard_data));
+			(tags, Vec::new())
+		}
 	};
+	for d in &tags_diagnostics {
+		eprintln!("skipped tags line {} : {}", d.line, d.message);
+	}
 	// F
-	let all_files = tag_entry::get_all_files(&tags_result)
-			.into_iter()
-			.cloned()
-			.collect::<Vec<String>>();
-	let all_files_refs = all_files.iter().collect::<Vec<&String>>();
-	let hard_data_ref =
-			tag_entry::get_all_hard_data(&all_files_refs, &tags_result).await;
-	let hard_data: HashMap<String, (Vec<ScopeEntry>, Vec<ClassEntry>, Vec<FunctionEntry>, Vec<ObjectEntry>)> =
-		hard_data_ref.into_iter()
-			.map(|(k, v)| (k.clone(), v))
-			.collect();
+	// every file is read and masked once, the passes below share it
+	let (all_files, sources, hard_data) = match built_in_data {
+		Some(data) => data,
+		None => {
+			let all_files = tag_entry::get_all_files(&tags_result)
+					.into_iter()
+					.cloned()
+					.collect::<Vec<String>>();
+			let all_files_refs = all_files.iter().collect::<Vec<&String>>();
+			let sources = read_sources(&all_files_refs);
+			let hard_data_ref =
+					tag_entry::get_all_hard_data(&all_files_refs, &sources, &tags_result).await;
+			let hard_data: HashMap<String, (Vec<ScopeEntry>, Vec<ClassEntry>, Vec<FunctionEntry>, Vec<ObjectEntry>)> =
+				hard_data_ref.into_iter()
+					.map(|(k, v)| (k.clone(), v))
+					.collect();
+			(all_files, sources, hard_data)
+		}
+	};
 	let all_files_refs = all_files.iter().collect::<Vec<&String>>();
 	let hard_data_refs: HashMap<&String, (Vec<ScopeEntry>, Vec<ClassEntry>, Vec<FunctionEntry>, Vec<ObjectEntry>)> =
 		hard_data.iter().map(|(k, v)| (k, v.clone())).collect();
-	let (raw_imports, all_tags, children_tags) = evaluate_imports::evaluate_all_hard_data(
+	let (raw_imports, all_tags, children_tags, imported_names, symbol_imports, external_dependencies, include_closure) = evaluate_imports::evaluate_all_hard_data(
 			&project_path,
 			&all_files_refs,
+			&sources,
 			hard_data_refs,
 	);
 
+	let source_modules =
+		source_modules::pair_sources(&all_files, &raw_imports, &all_tags, &children_tags);
+
+	let include_costs = include_costs::include_costs(&all_files, &include_closure);
+
 	// let (imports_json, tags_json, children_json) =
 	// 		evaluate_imports::jsonify_evaluated_data(&raw_imports, &all_tags, &children_tags);
 
@@ -79,7 +146,7 @@ pub async fn create_project_data(
 	println!("\n\n------ intense extract ------\n\n");
 	let all_files_refs = all_files.iter().collect::<Vec<&String>>();
 	let (custom_classes, accessible_scopes, scoped_connectable_s) =
-			intense_evaluation::evaluate(&project_path, &all_files_refs);
+			intense_evaluation::evaluate(&all_files_refs, &sources, &raw_imports, &imported_names, &include_closure);
 
 	// Serialize data
 	// let intense_data_json = json!({
@@ -89,16 +156,22 @@ pub async fn create_project_data(
 	// });
 
 	//
-	ProjectData {
+	Ok(ProjectData {
 		project_path,
 		tags_data: tags_result,
+		tags_diagnostics,
 		all_files,
 		hard_data,
 		raw_imports,
+		imported_names,
+		symbol_imports,
+		external_dependencies,
 		all_tags,
 		children_tags,
+		source_modules,
+		include_costs,
 		custom_classes,
 		accessible_scopes,
     scoped_connectables: scoped_connectable_s,
-	}
+	})
 }
\ No newline at end of file
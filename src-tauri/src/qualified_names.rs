@@ -0,0 +1,191 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::{
+    code_lexer::SourceFiles,
+    data::get_language_key,
+    tag_entry::{ClassEntry, FunctionEntry, ObjectEntry, ScopeEntry},
+};
+
+/// `ns::Outer` + `Inner` -> `ns::Outer::Inner`
+pub fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        return name.to_string();
+    }
+    format!("{}::{}", prefix, name)
+}
+
+/// `ns::Outer::Inner` -> `ns::Outer`, empty for a global name
+pub fn parent_path(qualified: &str) -> &str {
+    match qualified.rsplit_once("::") {
+        Some((parent, _)) => parent,
+        None => "",
+    }
+}
+
+/// `ns::Outer::Inner` -> `Inner`
+pub fn simple_name(qualified: &str) -> &str {
+    match qualified.rsplit_once("::") {
+        Some((_, name)) => name,
+        None => qualified,
+    }
+}
+
+/// fills the qualified names of the entries of one file from its scope tree
+///
+/// an entry is qualified by the classes and namespaces whose scopes enclose
+/// it, a member found outside of its class falls back to the owner the tags
+/// file reported
+pub fn qualify_hard_data(
+    scopes: &[ScopeEntry],
+    classes: &mut [ClassEntry],
+    functions: &mut [FunctionEntry],
+    objects: &mut [ObjectEntry],
+) {
+    // scope -> name of the class that owns it
+    let mut scope_owners: HashMap<usize, String> = HashMap::new();
+    for c in classes.iter() {
+        if c.class_scope != c.parent_scope {
+            scope_owners.insert(c.class_scope, c.name.clone());
+        }
+    }
+    let path_of = |scope: usize| -> String {
+        let mut names = Vec::new();
+        let mut s = scope;
+        while s < scopes.len() {
+            if let Some(name) = scope_owners.get(&s) {
+                names.push(name.as_str());
+            }
+            let p = scopes[s].parent_scope;
+            if p == u128::MAX {
+                break;
+            }
+            s = p as usize;
+        }
+        names.reverse();
+        names.join("::")
+    };
+    let member_path = |scope: usize, owner: &Option<String>| -> String {
+        match (path_of(scope), owner) {
+            (path, Some(owner)) if path.is_empty() => owner.clone(),
+            (path, _) => path,
+        }
+    };
+
+    for c in classes.iter_mut() {
+        c.qualified_name = join(&path_of(c.parent_scope), &c.name);
+    }
+    for f in functions.iter_mut() {
+        let path = match &f.qualifier {
+            // `int Mammal::getAge() {` names its class from where it is written
+            Some(qualifier) => join(&path_of(f.parent_scope), qualifier),
+            None => member_path(f.parent_scope, &f.owner),
+        };
+        f.qualified_name = join(&path, &f.name);
+    }
+    for o in objects.iter_mut() {
+        o.qualified_name = join(&member_path(o.parent_scope, &o.owner), &o.name);
+    }
+}
+
+lazy_static! {
+    static ref USING_NAMESPACE: Regex =
+        Regex::new(r"\busing\s+namespace\s+((?:::)?\w+(?:::\w+)*)\s*;").unwrap();
+    static ref USING_DECLARATION: Regex =
+        Regex::new(r"\busing\s+((?:::)?\w+(?:::\w+)+)\s*;").unwrap();
+}
+
+/// `using namespace` directives and `using` declarations of a file
+#[derive(Debug, Default)]
+pub struct UsingDirectives {
+    /// `using namespace geo;` -> `geo`
+    pub namespaces: Vec<String>,
+    /// `using geo::Point;` -> `geo::Point`
+    pub declarations: Vec<String>,
+}
+
+/// reads the `using` lines of a c++ file from its code in `sources`, other languages have none
+pub fn read_using_directives(file_path: &String, sources: &SourceFiles) -> UsingDirectives {
+    let mut usings = UsingDirectives::default();
+    if get_language_key(file_path).as_deref() != Some("cpp") {
+        return usings;
+    }
+    let code_text = match sources.get(file_path) {
+        Some(source) => &source.code,
+        None => return usings,
+    };
+
+    for caps in USING_NAMESPACE.captures_iter(&code_text) {
+        usings
+            .namespaces
+            .push(caps[1].trim_start_matches("::").to_string());
+    }
+    for caps in USING_DECLARATION.captures_iter(&code_text) {
+        usings
+            .declarations
+            .push(caps[1].trim_start_matches("::").to_string());
+    }
+    usings
+}
+
+/// qualified names `query` may refer to from inside `context`, most likely first
+///
+/// the enclosing scopes are tried from the innermost out, then the `using`
+/// declarations and directives, a `::Name` query only matches a global name
+pub fn lookup_candidates(context: &str, query: &str, usings: &UsingDirectives) -> Vec<String> {
+    if let Some(global) = query.strip_prefix("::") {
+        return vec![global.to_string()];
+    }
+
+    let mut candidates = Vec::new();
+    let mut prefix = context;
+    loop {
+        candidates.push(join(prefix, query));
+        if prefix.is_empty() {
+            break;
+        }
+        prefix = parent_path(prefix);
+    }
+
+    let first_name = query.split("::").next().unwrap_or(query);
+    for declaration in &usings.declarations {
+        if simple_name(declaration) == first_name {
+            candidates.push(join(parent_path(declaration), query));
+        }
+    }
+    for namespace in &usings.namespaces {
+        candidates.push(join(namespace, query));
+    }
+
+    let mut unique = Vec::new();
+    for c in candidates {
+        if !unique.contains(&c) {
+            unique.push(c);
+        }
+    }
+    unique
+}
+
+/// index of the name the first candidate matches
+///
+/// when no candidate matches exactly, a name with the same simple name is
+/// taken as long as it is the only one, `accept` can rule names out
+pub fn resolve(
+    candidates: &[String],
+    names: &[&str],
+    accept: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    for candidate in candidates {
+        if let Some(i) = (0..names.len()).find(|i| names[*i] == candidate && accept(*i)) {
+            return Some(i);
+        }
+    }
+
+    let wanted = simple_name(candidates.first()?);
+    let mut same_name = (0..names.len()).filter(|i| simple_name(names[*i]) == wanted && accept(*i));
+    match (same_name.next(), same_name.next()) {
+        (Some(i), None) => Some(i),
+        _ => None,
+    }
+}
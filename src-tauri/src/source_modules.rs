@@ -0,0 +1,131 @@
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::data::{get_language_key, is_header};
+use crate::evaluate_imports::ProgramTag;
+
+/// why a source file was paired with a header
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub enum PairReason {
+    /// `Foo.h` and `Foo.cpp`
+    SameName,
+    /// the source includes the header
+    Include,
+    /// the source defines members of classes declared in the header
+    OutOfLineDefinitions,
+}
+
+/// a header and the source files that implement it, shown as one unit
+#[derive(Debug, Serialize, Clone)]
+pub struct SourceModule {
+    /// path of the header without its extension, `src/Foo`
+    pub name: String,
+    pub header: usize,
+    pub sources: Vec<usize>,
+    /// `(source, reasons)`, why every source was paired
+    pub reasons: Vec<(usize, Vec<PairReason>)>,
+    /// `(file, tag)` of the declarations of the header followed by the
+    /// definitions of the sources
+    pub tags: Vec<(usize, usize)>,
+}
+
+/// pairs every c / c++ source with the header it implements
+///
+/// a source and a header pair up when they share a name and the source
+/// includes the header or sits next to it, or when the source defines members
+/// of the classes of the header, `int Foo::bar() {`, a source implementing
+/// more than one header joins the one it defines the most for
+pub fn pair_sources(
+    all_files: &[String],
+    raw_imports: &HashMap<usize, Vec<usize>>,
+    all_tags: &HashMap<usize, Vec<ProgramTag>>,
+    children_tags: &HashMap<(usize, usize), Vec<(usize, usize)>>,
+) -> Vec<SourceModule> {
+    let is_source = |f: usize| {
+        matches!(
+            get_language_key(&all_files[f]).as_deref(),
+            Some("c" | "cpp")
+        ) && !is_header(&all_files[f])
+    };
+
+    // (source, header) -> functions of the source that are children of classes of the header
+    let mut definitions: HashMap<(usize, usize), usize> = HashMap::new();
+    for ((class_file, class_tag), children) in children_tags {
+        if !is_header(&all_files[*class_file]) || !all_tags[class_file][*class_tag].is_class() {
+            continue;
+        }
+        for (child_file, child_tag) in children {
+            if is_source(*child_file) {
+                if let ProgramTag::Function { .. } = all_tags[child_file][*child_tag] {
+                    *definitions.entry((*child_file, *class_file)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut modules: Vec<SourceModule> = Vec::new();
+    for source in (0..all_files.len()).filter(|f| is_source(*f)) {
+        let includes = raw_imports.get(&source).cloned().unwrap_or_default();
+        let source_path = Path::new(&all_files[source]);
+
+        // the header with the most evidence, then the most definitions
+        let mut best: Option<(usize, Vec<PairReason>, usize)> = None;
+        for header in (0..all_files.len()).filter(|f| is_header(&all_files[*f])) {
+            let header_path = Path::new(&all_files[header]);
+            let mut reasons = Vec::new();
+            let same_name = header_path.file_stem() == source_path.file_stem();
+            let included = includes.contains(&header);
+            if same_name && (included || header_path.parent() == source_path.parent()) {
+                reasons.push(PairReason::SameName);
+            }
+            if included {
+                reasons.push(PairReason::Include);
+            }
+            let defined = definitions.get(&(source, header)).copied().unwrap_or(0);
+            if defined > 0 {
+                reasons.push(PairReason::OutOfLineDefinitions);
+            }
+            // an include alone is a use of the header, not its implementation
+            if !reasons.contains(&PairReason::SameName) && defined == 0 {
+                continue;
+            }
+            let better = match &best {
+                Some((_, best_reasons, best_defined)) => {
+                    (reasons.len(), defined) > (best_reasons.len(), *best_defined)
+                }
+                None => true,
+            };
+            if better {
+                best = Some((header, reasons, defined));
+            }
+        }
+
+        if let Some((header, reasons, _)) = best {
+            match modules.iter_mut().find(|m| m.header == header) {
+                Some(module) => {
+                    module.sources.push(source);
+                    module.reasons.push((source, reasons));
+                }
+                None => modules.push(SourceModule {
+                    name: Path::new(&all_files[header])
+                        .with_extension("")
+                        .to_string_lossy()
+                        .to_string(),
+                    header,
+                    sources: vec![source],
+                    reasons: vec![(source, reasons)],
+                    tags: Vec::new(),
+                }),
+            }
+        }
+    }
+
+    for module in modules.iter_mut() {
+        for f in std::iter::once(module.header).chain(module.sources.iter().copied()) {
+            let file_tags = all_tags.get(&f).map_or(0, |t| t.len());
+            module.tags.extend((0..file_tags).map(|t| (f, t)));
+        }
+    }
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    modules
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// a region of a source file
+///
+/// offsets are bytes into the file and `end` is exclusive, lines and columns
+/// are 1 based and columns count chars, so editors can jump straight to them
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}..{}:{}",
+            self.start_line, self.start_col, self.end_line, self.end_col
+        )
+    }
+}
+
+/// byte offset -> line / column lookups for one file
+pub struct LineIndex<'a> {
+    text: &'a str,
+    /// byte offset at which every line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> LineIndex<'a> {
+        let mut line_starts = vec![0];
+        for (b_i, b) in text.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push(b_i + 1);
+            }
+        }
+        LineIndex { text, line_starts }
+    }
+
+    /// `(line, column)` of a byte offset
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+        let line_i = match self.line_starts.binary_search(&offset) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        let line_start = self.line_starts[line_i];
+        let col = match self.text.get(line_start..offset) {
+            Some(before) => before.chars().count() + 1,
+            // inside a multi byte char, count the bytes instead
+            None => offset - line_start + 1,
+        };
+        (line_i + 1, col)
+    }
+
+    /// byte offset at which a 1 based line starts
+    pub fn line_start(&self, line: usize) -> usize {
+        match line.checked_sub(1).and_then(|l| self.line_starts.get(l)) {
+            Some(start) => *start,
+            None => self.text.len(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn span(&self, start: usize, end: usize) -> Span {
+        let (start_line, start_col) = self.position(start);
+        let (end_line, end_col) = self.position(end);
+        Span {
+            start,
+            end,
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        }
+    }
+}
@@ -1,111 +1,325 @@
+pub mod ctags_json;
+pub mod ctags_runner;
+pub mod extension_fields;
+pub mod file_filter;
 pub mod file_walk;
 pub mod serialization;
+pub mod tag_address;
 
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
-    io::{self, BufRead},
+    fs, io,
     path::Path,
 };
 
+use crate::code_lexer::SourceFiles;
+use crate::span::Span;
+use extension_fields::parse_extension_fields;
 use file_walk::file_walk;
-use serde::Serialize;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serialization::{u128_as_string, vec_u128_as_string};
+use tag_address::{parse_address, TagAddress};
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Default, Serialize, Clone)]
 pub struct TagEntry {
     pub tag_name: String,
     pub file_name: String,
     pub reg_ex: String,
     pub tag: String,
     pub context: String,
+    /// line of the definition, 1 based
+    pub line: Option<usize>,
+    /// name of the enclosing class / namespace / function
+    pub scope: Option<String>,
+    /// kind of the enclosing scope (`class`, `namespace`, ...)
+    pub scope_kind: Option<String>,
+    pub signature: Option<String>,
+    pub access: Option<String>,
+    /// type of a variable or return type of a function (`typename:int`)
+    pub typeref: Option<String>,
+    /// base classes listed by `inherits:`
+    pub inherits: Vec<String>,
+    /// template parameter list of a c++ class or function, `<typename T>`
+    pub template: Option<String>,
+    /// `file:` is set, the tag is not visible outside its file
+    pub file_scope: bool,
 }
 
 #[derive(Serialize, Clone)]
 pub struct ScopeEntry {
     pub file_name: String,
-    #[serde(serialize_with = "u128_as_string")]
-    pub start_line: u128,
-    #[serde(serialize_with = "u128_as_string")]
-    pub start_col: u128,
-    #[serde(serialize_with = "u128_as_string")]
-    pub end_line: u128,
-    #[serde(serialize_with = "u128_as_string")]
-    pub end_col: u128,
+    /// from the opening brace to the closing one, the whole file for the root scope
+    pub span: Span,
     #[serde(serialize_with = "u128_as_string")]
     pub parent_scope: u128,
     #[serde(serialize_with = "vec_u128_as_string")]
     pub children_scop: Vec<u128>,
 }
 
+/// the type like definitions that are shown as classes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ClassKind {
+    Class,
+    Struct,
+    Union,
+    Enum,
+    Namespace,
+    Typedef,
+    /// a rust trait or a typescript interface
+    Interface,
+    /// a rust `impl` block or a later part of a c# `partial class`, it adds
+    /// members and bases to a type declared elsewhere
+    Implementation,
+}
+
+impl ClassKind {
+    /// ctags kind letter or full kind name, `s` / `struct`
+    pub fn from_tag(tag: &str) -> Option<ClassKind> {
+        match tag {
+            "c" | "class" => Some(ClassKind::Class),
+            "s" | "struct" => Some(ClassKind::Struct),
+            "u" | "union" => Some(ClassKind::Union),
+            "g" | "enum" => Some(ClassKind::Enum),
+            "n" | "namespace" => Some(ClassKind::Namespace),
+            "t" | "typedef" => Some(ClassKind::Typedef),
+            "i" | "interface" => Some(ClassKind::Interface),
+            "implementation" => Some(ClassKind::Implementation),
+            _ => None,
+        }
+    }
+
+    /// keyword the definition starts with, `enum class` is an enum
+    pub fn from_keyword(keyword: &str) -> Option<ClassKind> {
+        match keyword.split_whitespace().next()? {
+            // a c# `record struct` is a struct
+            "record" if keyword.ends_with("struct") => Some(ClassKind::Struct),
+            // a java `record` and a kotlin `object` are classes too
+            "class" | "record" | "object" => Some(ClassKind::Class),
+            "struct" => Some(ClassKind::Struct),
+            "union" => Some(ClassKind::Union),
+            "enum" => Some(ClassKind::Enum),
+            "namespace" | "mod" | "module" => Some(ClassKind::Namespace),
+            "typedef" | "using" => Some(ClassKind::Typedef),
+            "trait" | "interface" => Some(ClassKind::Interface),
+            "impl" => Some(ClassKind::Implementation),
+            _ => None,
+        }
+    }
+
+    /// ctags kind letter
+    pub fn tag(&self) -> &'static str {
+        match self {
+            ClassKind::Class => "c",
+            ClassKind::Struct => "s",
+            ClassKind::Union => "u",
+            ClassKind::Enum => "g",
+            ClassKind::Namespace => "n",
+            ClassKind::Typedef => "t",
+            ClassKind::Interface => "i",
+            // ctags writes `c` for rust, that letter is taken by classes
+            ClassKind::Implementation => "implementation",
+        }
+    }
+
+    /// ctags kind name, also used as `scope_kind` of the members
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClassKind::Class => "class",
+            ClassKind::Struct => "struct",
+            ClassKind::Union => "union",
+            ClassKind::Enum => "enum",
+            ClassKind::Namespace => "namespace",
+            ClassKind::Typedef => "typedef",
+            ClassKind::Interface => "interface",
+            ClassKind::Implementation => "implementation",
+        }
+    }
+
+    /// access of a base or member listed without one, `struct A : B` inherits publicly
+    pub fn default_access(&self) -> Access {
+        match self {
+            ClassKind::Class => Access::Private,
+            _ => Access::Public,
+        }
+    }
+}
+
+lazy_static! {
+    /// `public:` / `protected:` / `private:` label of a c++ class body, not a `private::` path
+    pub static ref ACCESS_LABEL: Regex =
+        Regex::new(r"\b(public|protected|private)\s*:(?:[^:]|$)").unwrap();
+}
+
+/// c++ access of a member or a base class
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Access {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Access {
+    /// `public`, `protected` or `private`, as written in code or by ctags `access:`
+    pub fn from_keyword(keyword: &str) -> Option<Access> {
+        match keyword.trim() {
+            "public" => Some(Access::Public),
+            "protected" => Some(Access::Protected),
+            "private" => Some(Access::Private),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Access::Public => "public",
+            Access::Protected => "protected",
+            Access::Private => "private",
+        }
+    }
+}
+
 #[derive(Clone, Serialize)]
 pub struct ClassEntry {
     pub name: String,
+    /// `ns::Outer::Inner`, see `qualified_names::qualify_hard_data`
+    pub qualified_name: String,
+    pub kind: ClassKind,
+    /// from the definition to the end of the class body
+    pub span: Span,
     pub parent_scope: usize,
+    /// the parent scope for a typedef, it has no body
     pub class_scope: usize,
-    pub parents: Vec<String>,
+    /// `T` of `template<typename T> class Box`
+    pub template_params: Vec<String>,
+    /// base classes with the access they are inherited with, the aliased type for a typedef
+    pub parents: Vec<(Access, String)>,
+    /// `partial class Dog` of c#, one of the parts `Dog` is declared in
+    pub partial: bool,
 }
 
 #[derive(Clone, Serialize)]
 pub struct FunctionEntry {
     pub name: String,
+    /// `ns::Class::function`
+    pub qualified_name: String,
+    /// from the definition to the end of the function body
+    pub span: Span,
     pub parent_scope: usize,
     pub function_scope: usize,
+    /// `T` of `template<typename T> T max(T a, T b)`
+    pub template_params: Vec<String>,
     pub class_name: String,
+    /// class the function is a member of, as reported by the tags file
+    pub owner: Option<String>,
+    /// `Mammal` of an out of line definition, `int Mammal::getAge() {`
+    pub qualifier: Option<String>,
+    /// none for a function that is not a member
+    pub access: Option<Access>,
+    /// `(Context, *Dog) (error)`, the types of the parameters and the results
+    /// of a go function, see `type_ref::go_signature`
+    pub signature: Option<String>,
+    /// `func (r *Repo) Save()` of go, not a method of a `Repo` value
+    pub pointer_receiver: bool,
 }
 
 #[derive(Clone, Serialize)]
 pub struct ObjectEntry {
     pub name: String,
+    /// `ns::Class::member`
+    pub qualified_name: String,
+    pub span: Span,
     pub parent_scope: usize,
     pub class_name: String,
+    /// class the object is a member of, as reported by the tags file
+    pub owner: Option<String>,
+    /// none for a variable that is not a member
+    pub access: Option<Access>,
     // default value
 }
 
-pub fn get_tags_data(tags_path: String) -> io::Result<Vec<TagEntry>> {
+/// an entry of the tags file that had to be skipped
+#[derive(Debug, Serialize, Clone)]
+pub struct TagDiagnostic {
+    /// line in the tags file, 1 based
+    pub line: usize,
+    pub message: String,
+}
+
+pub fn get_tags_data(tags_path: String) -> io::Result<(Vec<TagEntry>, Vec<TagDiagnostic>)> {
+    // non utf-8 bytes in a pattern should not cost the whole file
+    let bytes = fs::read(Path::new(&tags_path))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let lines = text.lines().map(|l| l.to_string()).collect::<Vec<String>>();
+
+    Ok(read_tags_lines(&lines))
+}
+
+/// picks the reader by looking at the lines themselves
+pub fn read_tags_lines(lines: &[String]) -> (Vec<TagEntry>, Vec<TagDiagnostic>) {
+    // universal ctags `--output-format=json` writes one object per line
+    if ctags_json::is_json_tags(lines) {
+        return ctags_json::read_json_tags(lines);
+    }
+
+    read_tab_tags(lines)
+}
+
+/// `name<TAB>file<TAB>address;"<TAB>fields...`
+fn read_tab_tags(lines: &[String]) -> (Vec<TagEntry>, Vec<TagDiagnostic>) {
     let mut tags = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for (line_i, line) in lines.iter().enumerate() {
+        // Skip comment lines in the tags file
+        if line.starts_with("!") || line.trim().is_empty() {
+            continue;
+        }
+        let mut reject = |message: String| {
+            diagnostics.push(TagDiagnostic {
+                line: line_i + 1,
+                message,
+            })
+        };
+
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 3 {
+            reject(format!(
+                "expected at least 3 columns, found {}",
+                parts.len()
+            ));
+            continue;
+        }
+        if parts[0].is_empty() || parts[1].is_empty() {
+            reject("missing tag name or file name".to_string());
+            continue;
+        }
+        // the address may itself hold tabs, it ends at the first `;"`
+        let (address, fields) = match parts.iter().position(|p| p.ends_with(";\"")) {
+            Some(end) if end >= 2 => (parts[2..=end].join("\t"), &parts[end + 1..]),
+            _ => (parts[2].to_string(), &parts[3..]),
+        };
 
-    // Open the file.
-    let path = Path::new(&tags_path);
-    let file = File::open(path)?;
-
-    // Read the file line by line
-    let reader = io::BufReader::new(file);
-    for line in reader.lines() {
-        let line = line?; // Handle potential I/O errors
-        if !line.starts_with("!") {
-            // Skip comment lines in the tags file
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() >= 3 {
-                let tag_name = parts[0].to_string();
-                let file_name = parts[1].to_string();
-                let reg_ex = parts[2]
-                    .to_string()
-                    .get(2..parts[2].to_string().len() - 4)
-                    .unwrap()
-                    .trim()
-                    .to_string();
-                let tag = if parts.len() >= 4 {
-                    parts[3].to_string()
-                } else {
-                    "".to_string()
-                };
-                let context = if parts.len() >= 5 {
-                    parts[4].to_string()
-                } else {
-                    "".to_string()
-                };
-                tags.push(TagEntry {
-                    tag_name: tag_name,
-                    file_name: file_name,
-                    reg_ex: reg_ex,
-                    tag: tag,
-                    context: context,
-                });
+        let mut entry = TagEntry {
+            tag_name: parts[0].to_string(),
+            file_name: parts[1].to_string(),
+            context: fields.get(1).map(|c| c.to_string()).unwrap_or_default(),
+            ..Default::default()
+        };
+        match parse_address(&address) {
+            Ok(TagAddress::Pattern(pattern)) => entry.reg_ex = pattern,
+            Ok(TagAddress::Line(line)) => entry.line = Some(line),
+            Err(e) => {
+                reject(format!("{} : {}", parts[0], e));
+                continue;
             }
         }
+        parse_extension_fields(&mut entry, fields);
+        tags.push(entry);
     }
-    Ok(tags)
+
+    (tags, diagnostics)
 }
 
 pub fn get_all_files<'a>(tags: &'a Vec<TagEntry>) -> HashSet<&'a String> {
@@ -119,6 +333,7 @@ pub fn get_all_files<'a>(tags: &'a Vec<TagEntry>) -> HashSet<&'a String> {
 
 pub async fn get_all_hard_data<'a>(
     all_files: &'a Vec<&'a String>,
+    sources: &SourceFiles,
     all_tags: &'a Vec<TagEntry>,
 ) -> HashMap<
     &'a String,
@@ -141,7 +356,13 @@ pub async fn get_all_hard_data<'a>(
         }
 
         // Process the file and collect the data
-        let file_data = file_walk(file_path, &tags);
+        let file_data = match sources.get(*file_path) {
+            Some(source) => file_walk(file_path, source, &tags),
+            None => {
+                println!("could not read this file {}", file_path);
+                (Vec::new(), Vec::new(), Vec::new(), Vec::new())
+            }
+        };
         all_data.insert(*file_path, file_data);
 
         // let progress = ((f + 1) as f32 / total_files as f32) * 100.0;
@@ -0,0 +1,147 @@
+use serde::Deserialize;
+
+use super::{
+    extension_fields::split_inherits,
+    tag_address::{parse_address, TagAddress},
+    TagDiagnostic, TagEntry,
+};
+
+/// one line of `ctags --output-format=json`
+///
+/// pseudo tags (`!_TAG_...`) come with `_type` set to `ptag` and are skipped
+#[derive(Deserialize)]
+struct JsonTag {
+    #[serde(rename = "_type")]
+    entry_type: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    path: String,
+    pattern: Option<String>,
+    line: Option<usize>,
+    kind: Option<String>,
+    scope: Option<String>,
+    #[serde(rename = "scopeKind")]
+    scope_kind: Option<String>,
+    signature: Option<String>,
+    access: Option<String>,
+    typeref: Option<String>,
+    /// `<typename T>`, written for c++ with `--fields-C++=+{template}`
+    template: Option<String>,
+    /// a string of base classes, some versions write `false` when there are none
+    inherits: Option<serde_json::Value>,
+    #[serde(default)]
+    file: bool,
+}
+
+/// the json output always starts every non empty line with an object
+pub fn is_json_tags(lines: &[String]) -> bool {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty())
+        .map_or(false, |l| l.starts_with('{'))
+}
+
+pub fn read_json_tags(lines: &[String]) -> (Vec<TagEntry>, Vec<TagDiagnostic>) {
+    let mut tags = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for (line_i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let json_tag: JsonTag = match serde_json::from_str(line) {
+            Ok(t) => t,
+            Err(e) => {
+                diagnostics.push(TagDiagnostic {
+                    line: line_i + 1,
+                    message: format!("invalid json : {}", e),
+                });
+                continue;
+            }
+        };
+        if json_tag.entry_type != "tag" {
+            continue;
+        }
+        if json_tag.name.is_empty() || json_tag.path.is_empty() {
+            diagnostics.push(TagDiagnostic {
+                line: line_i + 1,
+                message: "missing tag name or file name".to_string(),
+            });
+            continue;
+        }
+
+        let mut reg_ex = String::new();
+        let mut tag_line = json_tag.line;
+        if let Some(pattern) = &json_tag.pattern {
+            match parse_address(pattern) {
+                Ok(TagAddress::Pattern(p)) => reg_ex = p,
+                Ok(TagAddress::Line(l)) => tag_line = tag_line.or(Some(l)),
+                Err(e) => {
+                    diagnostics.push(TagDiagnostic {
+                        line: line_i + 1,
+                        message: format!("{} : {}", json_tag.name, e),
+                    });
+                    continue;
+                }
+            }
+        }
+
+        let tag = match &json_tag.kind {
+            Some(kind) => short_kind(kind).to_string(),
+            None => "".to_string(),
+        };
+        let context = match (&json_tag.scope_kind, &json_tag.scope) {
+            (Some(kind), Some(scope)) => format!("{}:{}", kind, scope),
+            _ => "".to_string(),
+        };
+
+        tags.push(TagEntry {
+            tag_name: json_tag.name,
+            file_name: json_tag.path,
+            reg_ex,
+            tag,
+            context,
+            line: tag_line,
+            scope: json_tag.scope,
+            scope_kind: json_tag.scope_kind,
+            signature: json_tag.signature,
+            access: json_tag.access,
+            typeref: json_tag.typeref,
+            template: json_tag.template,
+            inherits: json_tag
+                .inherits
+                .as_ref()
+                .and_then(|i| i.as_str())
+                .map(split_inherits)
+                .unwrap_or_default(),
+            file_scope: json_tag.file,
+        });
+    }
+
+    (tags, diagnostics)
+}
+
+/// json output names kinds in full (`class`, `member`), the rest of the
+/// pipeline works with the single letters of the exuberant ctags format
+fn short_kind(kind: &str) -> &str {
+    match kind {
+        "class" => "c",
+        "macro" => "d",
+        "enumerator" => "e",
+        "function" => "f",
+        "enum" => "g",
+        "header" => "h",
+        "local" => "l",
+        "member" => "m",
+        "namespace" => "n",
+        "prototype" => "p",
+        "struct" => "s",
+        "typedef" => "t",
+        "union" => "u",
+        "variable" => "v",
+        "externvar" => "x",
+        _ => kind,
+    }
+}
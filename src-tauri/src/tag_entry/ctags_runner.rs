@@ -0,0 +1,148 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use super::{
+    file_filter::{FileFilter, ScanOptions},
+    read_tags_lines, TagDiagnostic, TagEntry,
+};
+
+/// names ctags is installed under by the common package managers
+const CTAGS_NAMES: [&str; 5] = [
+    "ctags",
+    "universal-ctags",
+    "ctags-universal",
+    "uctags",
+    "exuberant-ctags",
+];
+
+/// what a ctags binary can be asked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CtagsFlavor {
+    /// `json` is false when it was built without libjansson
+    Universal { json: bool },
+    /// only knows the tab separated format
+    Exuberant,
+}
+
+/// first universal or exuberant ctags on `PATH`, `Err` with the binaries that
+/// were skipped when there is none
+///
+/// the bsd ctags of macos and the one of emacs are named `ctags` too, they know
+/// none of the flags used, so they are passed over
+pub fn find_ctags() -> Result<(PathBuf, CtagsFlavor), Vec<PathBuf>> {
+    let mut skipped = Vec::new();
+    let paths = env::var_os("PATH").unwrap_or_default();
+    for dir in env::split_paths(&paths) {
+        for name in CTAGS_NAMES {
+            for candidate in [dir.join(name), dir.join(format!("{}.exe", name))] {
+                if !candidate.is_file() || skipped.contains(&candidate) {
+                    continue;
+                }
+                match ctags_flavor(&candidate) {
+                    Some(flavor) => return Ok((candidate, flavor)),
+                    None => skipped.push(candidate),
+                }
+            }
+        }
+    }
+    Err(skipped)
+}
+
+/// runs ctags recursively over `project_path` and reads its output directly
+pub fn run_ctags(
+    project_path: &str,
+    options: &ScanOptions,
+) -> Result<(Vec<TagEntry>, Vec<TagDiagnostic>), String> {
+    let (ctags, flavor) = find_ctags().map_err(|skipped| match skipped.is_empty() {
+        true => "ctags was not found on PATH, install universal ctags (https://ctags.io) or pass a tags file"
+            .to_string(),
+        false => format!(
+            "{} is not universal or exuberant ctags, install universal ctags (https://ctags.io) or pass a tags file",
+            skipped
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    })?;
+    if !Path::new(project_path).is_dir() {
+        return Err(format!("project path {} is not a directory", project_path));
+    }
+    let filter = FileFilter::new(&options.include, &options.exclude)?;
+
+    let mut command = Command::new(&ctags);
+    match flavor {
+        CtagsFlavor::Universal { json: true } => {
+            command.args([
+                "--output-format=json",
+                "--fields=+nSaist",
+                "--fields-C++=+{template}",
+            ]);
+        }
+        CtagsFlavor::Universal { json: false } => {
+            command.args(["--fields=+nSaist", "--fields-C++=+{template}"]);
+        }
+        CtagsFlavor::Exuberant => {
+            command.args(["--fields=+naiSt"]);
+        }
+    }
+    command.args(["-R", "--sort=no", "-f", "-"]);
+    if !options.languages.is_empty() {
+        command.arg(format!("--languages={}", options.languages.join(",")));
+    }
+    // the exclude globs are matched against the project relative path, which
+    // `--exclude` does not, so they are applied to the tags once read
+    command.arg(project_path);
+
+    let output = command
+        .output()
+        .map_err(|e| format!("couldn't run {} : {}", ctags.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed with {} :\n{}",
+            ctags.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let lines = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<Vec<String>>();
+    let (tags, diagnostics) = read_tags_lines(&lines);
+    let tags = tags
+        .into_iter()
+        .filter(|t| filter.is_included(project_path, &t.file_name))
+        .collect();
+
+    Ok((tags, diagnostics))
+}
+
+/// `None` for a ctags that is neither universal nor exuberant ctags
+fn ctags_flavor(ctags: &Path) -> Option<CtagsFlavor> {
+    let output = Command::new(ctags).arg("--version").output().ok()?;
+    let version = String::from_utf8_lossy(&output.stdout);
+    if version.contains("Universal Ctags") {
+        // `json  no  no` when it was built without libjansson
+        let json = Command::new(ctags)
+            .arg("--list-output-formats")
+            .output()
+            .map_or(false, |formats| {
+                String::from_utf8_lossy(&formats.stdout)
+                    .lines()
+                    .any(|line| {
+                        let mut columns = line.split_whitespace();
+                        columns.next() == Some("json") && columns.next() == Some("yes")
+                    })
+            });
+        Some(CtagsFlavor::Universal { json })
+    } else if version.contains("Exuberant Ctags") {
+        Some(CtagsFlavor::Exuberant)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,95 @@
+use super::TagEntry;
+use crate::type_ref::split_top_level;
+
+/// kinds that ctags writes as `kind:name` to name the enclosing scope of a tag
+const SCOPE_KINDS: [&str; 7] = [
+    "class",
+    "struct",
+    "union",
+    "enum",
+    "namespace",
+    "function",
+    "interface",
+];
+
+/// fills the typed fields of `entry` from the columns after the address
+///
+/// `["f", "line:42", "class:Human", "signature:(int hit)"]`
+///
+/// a column without a `:` is the kind letter of the old format, `file:` marks
+/// a tag that is only visible inside its own file
+pub fn parse_extension_fields(entry: &mut TagEntry, fields: &[&str]) {
+    for field in fields {
+        let (key, value) = match field.split_once(':') {
+            Some(kv) => kv,
+            None => {
+                if entry.tag.is_empty() {
+                    entry.tag = field.to_string();
+                }
+                continue;
+            }
+        };
+        let value = unescape_field(value);
+
+        match key {
+            "kind" => entry.tag = value,
+            "line" => entry.line = value.parse().ok(),
+            "file" => entry.file_scope = true,
+            "signature" => entry.signature = Some(value),
+            "access" => entry.access = Some(value),
+            "typeref" => entry.typeref = Some(value),
+            "template" => entry.template = Some(value),
+            "inherits" => entry.inherits = split_inherits(&value),
+            // universal ctags can also write `scope:class:Human`
+            "scope" => {
+                if let Some((kind, name)) = value.split_once(':') {
+                    entry.scope_kind = Some(kind.to_string());
+                    entry.scope = Some(name.to_string());
+                }
+            }
+            _ if SCOPE_KINDS.contains(&key) => {
+                entry.scope_kind = Some(key.to_string());
+                entry.scope = Some(value);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `Mammal,Alive` -> `["Mammal", "Alive"]`, `Base<A,B>` stays whole
+pub fn split_inherits(value: &str) -> Vec<String> {
+    split_top_level(value, ',')
+        .into_iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// `typename:int` -> `int`
+pub fn typeref_name(typeref: &str) -> &str {
+    match typeref.split_once(':') {
+        Some((_, name)) => name,
+        None => typeref,
+    }
+}
+
+/// ctags escapes tabs, new lines and backslashes inside field values
+fn unescape_field(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => res.push('\t'),
+            Some('n') => res.push('\n'),
+            Some('r') => res.push('\r'),
+            Some(other) => res.push(other),
+            None => res.push('\\'),
+        }
+    }
+    res
+}
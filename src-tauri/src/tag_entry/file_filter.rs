@@ -0,0 +1,146 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+/// which files of the project are scanned for tags
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct ScanOptions {
+    /// ctags language names, `C`, `C++`, `Python`... all languages when empty
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// globs relative to the project, every file when empty
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// globs relative to the project
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// include / exclude globs checked against paths relative to the project
+///
+/// `*` and `?` stay inside one directory, `**` crosses directories and a glob
+/// without any `/` is matched against the file name in every directory
+pub struct FileFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl FileFilter {
+    pub fn new(include: &Vec<String>, exclude: &Vec<String>) -> Result<FileFilter, String> {
+        let build = |globs: &Vec<String>| {
+            globs
+                .iter()
+                .map(|g| {
+                    Regex::new(&glob_to_regex(g)).map_err(|e| format!("invalid glob {} : {}", g, e))
+                })
+                .collect::<Result<Vec<Regex>, String>>()
+        };
+        Ok(FileFilter {
+            include: build(include)?,
+            exclude: build(exclude)?,
+        })
+    }
+
+    /// an empty include list lets every file in
+    pub fn is_included(&self, project_path: &str, file_path: &str) -> bool {
+        let relative = relative_path(project_path, file_path);
+        if self.exclude.iter().any(|r| r.is_match(&relative)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|r| r.is_match(&relative))
+    }
+}
+
+/// `D:\project\src\a.cpp` in `D:\project` -> `src/a.cpp`
+pub fn relative_path(project_path: &str, file_path: &str) -> String {
+    let path = Path::new(file_path);
+    path.strip_prefix(project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let glob = glob.replace('\\', "/");
+    let glob = glob.trim_start_matches("./");
+    let mut res = String::from("^");
+    if !glob.contains('/') {
+        res.push_str("(?:.*/)?");
+    }
+
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    // `**/` may also match no directory at all
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        res.push_str("(?:.*/)?");
+                    } else {
+                        res.push_str(".*");
+                    }
+                } else {
+                    res.push_str("[^/]*");
+                }
+            }
+            '?' => res.push_str("[^/]"),
+            _ => res.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    // a directory glob also covers everything below it
+    res.push_str("(?:/.*)?$");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        Regex::new(&glob_to_regex(glob)).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        assert!(matches("src/**/*.cpp", "src/a.cpp"));
+        assert!(matches("src/**/*.cpp", "src/x/y/a.cpp"));
+        assert!(!matches("src/**/*.cpp", "lib/a.cpp"));
+        assert!(matches("**/test/*", "test/a.cpp"));
+        assert!(matches("**/test/*", "a/b/test/a.cpp"));
+    }
+
+    #[test]
+    fn single_star_stays_in_one_directory() {
+        assert!(matches("src/*.cpp", "src/a.cpp"));
+        assert!(!matches("src/*.cpp", "src/x/a.cpp"));
+        assert!(matches("src/?.h", "src/a.h"));
+        assert!(!matches("src/?.h", "src/ab.h"));
+    }
+
+    #[test]
+    fn name_globs_match_in_every_directory() {
+        assert!(matches("*.h", "a.h"));
+        assert!(matches("*.h", "include/x/a.h"));
+        assert!(!matches("*.h", "a.hpp"));
+    }
+
+    #[test]
+    fn directory_globs_cover_what_is_below() {
+        assert!(matches("third_party", "third_party/lib/a.cpp"));
+        assert!(matches("./build", "build/a.o"));
+        assert!(!matches("build", "rebuild/a.o"));
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_project() {
+        assert_eq!(relative_path("/p", "/p/src/a.cpp"), "src/a.cpp");
+        let filter = FileFilter::new(&vec![], &vec!["**/generated/**".to_string()]).unwrap();
+        assert!(filter.is_included("/p", "/p/src/a.cpp"));
+        assert!(!filter.is_included("/p", "/p/src/generated/a.cpp"));
+    }
+}
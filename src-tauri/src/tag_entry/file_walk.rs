@@ -1,15 +1,32 @@
 mod language_scanners;
-use super::{ClassEntry, FunctionEntry, ObjectEntry, ScopeEntry, TagEntry};
-use regex::Regex;
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-    u128,
+use super::{
+    extension_fields::typeref_name, Access, ClassEntry, ClassKind, FunctionEntry, ObjectEntry,
+    ScopeEntry, TagEntry, ACCESS_LABEL,
 };
+use crate::code_lexer::SourceFile;
+use crate::data::get_language_key;
+use crate::qualified_names::{qualify_hard_data, simple_name};
+use crate::span::LineIndex;
+use crate::type_ref::{go_signature, split_top_level, strip_template_clause};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{collections::HashMap, u128};
+
+/// words in front of a definition that are not part of its type
+const DEFINITION_SPECIFIERS: [&str; 8] = [
+    "static",
+    "virtual",
+    "inline",
+    "constexpr",
+    "explicit",
+    "friend",
+    "extern",
+    "mutable",
+];
 
 pub fn file_walk(
     file_path: &String,
+    source: &SourceFile,
     file_tags: &Vec<&TagEntry>,
 ) -> (
     Vec<ScopeEntry>,
@@ -17,12 +34,13 @@ pub fn file_walk(
     Vec<FunctionEntry>,
     Vec<ObjectEntry>,
 ) {
-    let res = brackets_walk(file_path, file_tags);
+    let res = brackets_walk(file_path, source, file_tags);
     res
 }
 
 fn brackets_walk(
     file_path: &String,
+    source: &SourceFile,
     tags: &Vec<&TagEntry>,
 ) -> (
     Vec<ScopeEntry>,
@@ -30,66 +48,116 @@ fn brackets_walk(
     Vec<FunctionEntry>,
     Vec<ObjectEntry>,
 ) {
-    // Open the file
-    let path = Path::new(file_path);
-    let file_r = File::open(path);
-    let file: File;
-    match file_r {
-        Ok(f) => {
-            file = f;
-        }
-        Err(_) => {
-            println!("could not read this file {}", file_path);
-            return (Vec::new(), Vec::new(), Vec::new(), Vec::new());
-        }
-    }
+    let file_text = &source.text;
+    // braces inside comments, literals and skipped `#else` branches are not scopes
+    let code_text = &source.code;
+    let positions = LineIndex::new(file_text);
+    let go = get_language_key(file_path).as_deref() == Some("go");
 
     let mut scope_entries: Vec<ScopeEntry> = Vec::new();
     let mut class_entries: Vec<ClassEntry> = Vec::new();
     let mut function_entries: Vec<FunctionEntry> = Vec::new();
     let mut object_entries: Vec<ObjectEntry> = Vec::new();
     let mut scope_stack: Vec<usize> = Vec::new();
-    let mut line_num: u128 = 0;
     let mut scope_scout_tag = '_';
+    // class scope -> access of the `public:` / `private:` section being walked
+    let mut scope_access: HashMap<usize, Access> = HashMap::new();
+    let mut previous_line = "";
 
     // region create file level scope
     scope_entries.push(ScopeEntry {
         file_name: file_path.clone(),
-        start_line: 0,
-        start_col: 0,
-        end_line: 0,
-        end_col: 0,
+        span: positions.span(0, file_text.len()),
         parent_scope: u128::MAX,
         children_scop: Vec::new(),
     });
     scope_stack.push(0);
     // endregion
 
-    let buf_reader = io::BufReader::new(&file);
-    for (line_index, line) in buf_reader.lines().enumerate() {
-        let line_content = line.unwrap();
+    for (line_index, (line_content, code_line)) in
+        file_text.lines().zip(code_text.lines()).enumerate()
+    {
+        let line_start = positions.line_start(line_index + 1);
+
+        // a label switches the access of the members that follow it
+        if let Some(&current_scope) = scope_stack.last() {
+            if let Some(access) = scope_access.get_mut(&current_scope) {
+                for caps in ACCESS_LABEL.captures_iter(code_line) {
+                    *access = Access::from_keyword(&caps[1]).unwrap_or(*access);
+                }
+            }
+        }
 
         for t in tags {
             // todo: also look for if else elif switch etc
 
-            if line_content.find(&t.reg_ex).is_some() {
-                let class_name_regex = Regex::new(r"(\w+) ").unwrap();
-
-                let class_name = match class_name_regex.captures(&line_content) {
-                    Some(n) => n[0].to_string(),
-                    None => "None".to_string(),
+            // a known line is exact, the pattern may also match other lines
+            let is_tag_line = match t.line {
+                Some(line) => line == line_index + 1,
+                None => !t.reg_ex.is_empty() && line_content.find(&t.reg_ex).is_some(),
+            };
+            if is_tag_line {
+                let (written_type, qualifier) = split_definition(line_content, &t.tag_name);
+                // prefer the type reported by ctags over the one written in front of the name
+                let class_name = match &t.typeref {
+                    Some(typeref) => typeref_name(typeref).to_string(),
+                    None => written_type.unwrap_or("None".to_string()),
                 }
                 .trim()
                 .to_string();
+                let owner = member_owner(t);
+                let template_params = find_template_params(t, line_content, previous_line);
+                // ctags `access:` wins over the section the member was found in
+                let access = t
+                    .access
+                    .as_deref()
+                    .and_then(Access::from_keyword)
+                    .or_else(|| {
+                        scope_stack
+                            .last()
+                            .and_then(|s| scope_access.get(s))
+                            .copied()
+                    });
+                // the definition starts where the pattern does, the body is added once the scope closes
+                let definition_start = line_start
+                    + line_content
+                        .find(&t.reg_ex)
+                        .unwrap_or(line_content.len() - line_content.trim_start().len());
+                let span = positions.span(definition_start, line_start + line_content.len());
 
                 // let line_parts: Vec<&str> = line_content.trim().split(" ").collect();
                 // let class_name = line_parts[0].to_string();
                 match t.tag.as_str() {
-                    "c" => {
-                        let parents = find_parents(&line_content);
-                        println!("parents -> {:?}", &parents);
+                    "c" | "s" | "u" | "g" | "n" | "i" => {
+                        let kind = ClassKind::from_tag(&t.tag).unwrap_or(ClassKind::Class);
+                        let parents = match kind {
+                            // `enum Color : char` names the underlying type, not a parent
+                            ClassKind::Enum | ClassKind::Namespace => Vec::new(),
+                            _ if t.inherits.is_empty() => find_parents(line_content, kind),
+                            // `inherits:` has no access, it is read from the definition line
+                            _ => {
+                                let line_parents = find_parents(line_content, kind);
+                                t.inherits
+                                    .iter()
+                                    .map(|base| {
+                                        let access = line_parents
+                                            .iter()
+                                            .find(|(_, name)| {
+                                                name == base
+                                                    || simple_name(name) == simple_name(base)
+                                            })
+                                            .map(|(access, _)| *access)
+                                            .unwrap_or(kind.default_access());
+                                        (access, base.clone())
+                                    })
+                                    .collect()
+                            }
+                        };
                         let new_class_entry = ClassEntry {
                             name: t.tag_name.clone(),
+                            qualified_name: t.tag_name.clone(),
+                            kind,
+                            span,
                             class_scope: if let Some(&parent_idx) = scope_stack.last() {
                                 parent_idx
                             } else {
@@ -100,14 +168,73 @@ fn brackets_walk(
                             } else {
                                 usize::MAX // Indicate no parent (root scope)
                             },
+                            template_params,
                             parents: parents,
+                            partial: is_partial(line_content),
                         };
                         class_entries.push(new_class_entry);
                         scope_scout_tag = 'c';
                     }
+                    "t" => {
+                        let current_scope = scope_stack.last().copied().unwrap_or(usize::MAX);
+                        // `} Name;` closes the body of `typedef struct { .. } Name;`
+                        let closes_body =
+                            code_line.trim_start().starts_with('}') && current_scope != 0;
+                        let new_typedef_entry = if closes_body {
+                            let body = &scope_entries[current_scope];
+                            ClassEntry {
+                                name: t.tag_name.clone(),
+                                qualified_name: t.tag_name.clone(),
+                                kind: ClassKind::Typedef,
+                                span: positions.span(body.span.start, span.end),
+                                parent_scope: body.parent_scope as usize,
+                                class_scope: current_scope,
+                                template_params: template_params.clone(),
+                                parents: typedef_target(t, line_content)
+                                    .map(|target| (Access::Public, target))
+                                    .into_iter()
+                                    .collect(),
+                                partial: false,
+                            }
+                        } else {
+                            // a plain alias has no body, it lives in the scope it is declared in
+                            ClassEntry {
+                                name: t.tag_name.clone(),
+                                qualified_name: t.tag_name.clone(),
+                                kind: ClassKind::Typedef,
+                                span,
+                                parent_scope: current_scope,
+                                class_scope: current_scope,
+                                template_params: template_params.clone(),
+                                parents: typedef_target(t, line_content)
+                                    .map(|target| (Access::Public, target))
+                                    .into_iter()
+                                    .collect(),
+                                partial: false,
+                            }
+                        };
+                        class_entries.push(new_typedef_entry);
+                    }
                     "f" => {
+                        // `func (r *Repo) Save(ctx context.Context) error {`
+                        let (signature, pointer_receiver) = match go {
+                            true => (
+                                t.signature.as_ref().map(|params| {
+                                    go_signature(
+                                        params.trim().trim_start_matches('(').trim_end_matches(')'),
+                                        t.typeref.as_deref().map_or("", typeref_name),
+                                    )
+                                }),
+                                GO_RECEIVER
+                                    .captures(line_content)
+                                    .map_or(false, |caps| caps[1].contains('*')),
+                            ),
+                            false => (None, false),
+                        };
                         let new_fn_entry = FunctionEntry {
                             name: t.tag_name.clone(),
+                            qualified_name: t.tag_name.clone(),
+                            span,
                             parent_scope: if let Some(&parent_idx) = scope_stack.last() {
                                 parent_idx
                             } else {
@@ -118,20 +245,34 @@ fn brackets_walk(
                             } else {
                                 usize::MAX // Indicate no parent (root scope)
                             },
+                            template_params,
                             class_name: class_name,
+                            owner: owner,
+                            qualifier: qualifier,
+                            access: access,
+                            signature,
+                            pointer_receiver,
                         };
                         function_entries.push(new_fn_entry);
                         scope_scout_tag = 'f';
                     }
-                    "m" => {
+                    "m" | "e" => {
                         let new_obj_entry = ObjectEntry {
                             name: t.tag_name.clone(),
+                            qualified_name: t.tag_name.clone(),
+                            span,
                             parent_scope: if let Some(&parent_idx) = scope_stack.last() {
                                 parent_idx
                             } else {
                                 usize::MAX // Indicate no parent (root scope)
                             },
-                            class_name: class_name,
+                            // an enumerator is of the type of its enum
+                            class_name: match (t.tag.as_str(), &t.scope) {
+                                ("e", Some(enum_name)) => enum_name.clone(),
+                                _ => class_name,
+                            },
+                            owner: owner,
+                            access: access,
                         };
                         object_entries.push(new_obj_entry);
                         // TODO: scope_scout_tag = 'm';
@@ -141,21 +282,16 @@ fn brackets_walk(
             }
         }
 
-        let mut col_num: u128 = 0;
-        line_num = line_index as u128 + 1; // Line numbers start from 1
-
         // Traverse each character in the line
-        for ch in line_content.chars() {
-            col_num += 1;
+        for (b_i, ch) in code_line.char_indices() {
+            let offset = line_start + b_i;
 
             // If we encounter an opening brace '{', create a new ScopeEntry
             if ch == '{' {
                 let new_scope = ScopeEntry {
                     file_name: file_path.clone(),
-                    start_line: line_num,
-                    start_col: col_num,
-                    end_line: 0, // Placeholder, will be updated when the scope ends
-                    end_col: 0,  // Placeholder, will be updated when the scope ends
+                    // Placeholder end, will be updated when the scope ends
+                    span: positions.span(offset, offset + 1),
                     parent_scope: if let Some(&parent_idx) = scope_stack.last() {
                         parent_idx as u128
                     } else {
@@ -179,7 +315,14 @@ fn brackets_walk(
                 match scope_scout_tag {
                     'c' => {
                         if let Some(class_entry) = class_entries.last_mut() {
-                            class_entry.class_scope = new_scope_idx
+                            class_entry.class_scope = new_scope_idx;
+                            // members start with the default access of their class
+                            if let ClassKind::Class | ClassKind::Struct | ClassKind::Union =
+                                class_entry.kind
+                            {
+                                scope_access
+                                    .insert(new_scope_idx, class_entry.kind.default_access());
+                            }
                         }
                     }
                     'f' => {
@@ -189,6 +332,8 @@ fn brackets_walk(
                     }
                     _ => {}
                 }
+                // only the first brace after the tag opens its body
+                scope_scout_tag = '_';
 
                 // Push this scope's index onto the stack (to denote it's the current scope)
                 scope_stack.push(new_scope_idx);
@@ -196,54 +341,41 @@ fn brackets_walk(
             // If we encounter a closing brace '}', finalize the current ScopeEntry
             else if ch == '}' {
                 if let Some(scope_idx) = scope_stack.pop() {
-                    // Update the end line and column for the last scope on the stack
-                    scope_entries[scope_idx].end_line = line_num;
-                    scope_entries[scope_idx].end_col = col_num;
+                    // Update the end of the last scope on the stack
+                    let start = scope_entries[scope_idx].span.start;
+                    scope_entries[scope_idx].span = positions.span(start, offset + 1);
                 } else {
-                    println!(
-                        "Unmatched closing brace at line {} column {}",
-                        line_num, col_num
-                    );
+                    let (line, col) = positions.position(offset);
+                    println!("Unmatched closing brace at line {} column {}", line, col);
                 }
             }
         }
+        previous_line = line_content;
     }
 
-    // update the ending values of class scope
-    if let Some(scope_idx) = scope_stack.pop() {
-        scope_entries[scope_idx].end_line = line_num;
-        scope_entries[scope_idx].end_col = u128::MAX;
+    // scopes that are still open, the file scope at least, end with the file
+    while let Some(scope_idx) = scope_stack.pop() {
+        let start = scope_entries[scope_idx].span.start;
+        scope_entries[scope_idx].span = positions.span(start, file_text.len());
     }
 
-    println!("in {}", &file_path);
-    println!("scopes");
-    for s in &scope_entries {
-        println!(
-            "\t{}..{}, p->{}, c->({})",
-            s.start_line,
-            s.end_line,
-            s.parent_scope,
-            s.children_scop
-                .iter()
-                .map(|num| num.to_string()) // Convert each u128 to String
-                .collect::<Vec<String>>()
-                .join(",")
-        );
-    }
-    println!("classes");
-    for c in &class_entries {
-        println!("\t{} in {} of {}", c.name, c.parent_scope, c.class_scope);
-    }
-    println!("functions");
-    for f in &function_entries {
-        println!(
-            "\t{} {} in {} of {}",
-            f.class_name, f.name, f.parent_scope, f.function_scope
-        );
+    qualify_hard_data(
+        &scope_entries,
+        &mut class_entries,
+        &mut function_entries,
+        &mut object_entries,
+    );
+
+    // classes and functions span their whole body
+    for c in class_entries.iter_mut() {
+        if c.kind != ClassKind::Typedef && c.class_scope != c.parent_scope {
+            c.span = positions.span(c.span.start, scope_entries[c.class_scope].span.end);
+        }
     }
-    println!("objects");
-    for m in &object_entries {
-        println!("\t{} {} in {}", m.class_name, m.name, m.parent_scope);
+    for f in function_entries.iter_mut() {
+        if f.function_scope != f.parent_scope {
+            f.span = positions.span(f.span.start, scope_entries[f.function_scope].span.end);
+        }
     }
 
     (
@@ -254,13 +386,202 @@ fn brackets_walk(
     )
 }
 
-fn find_parents(line: &String) -> Vec<String> {
-    // Corrected regex pattern to capture access specifiers and class names.
-    let parent_regex = Regex::new(r"(public|private|protected)\s+(\w+)").unwrap();
+/// name of the class, struct, enum or namespace a tag belongs to, if the
+/// tags file says so
+fn member_owner(tag: &TagEntry) -> Option<String> {
+    match tag.scope_kind.as_deref().and_then(ClassKind::from_tag) {
+        Some(ClassKind::Typedef) | None => None,
+        Some(_) => tag.scope.clone(),
+    }
+}
+
+lazy_static! {
+    static ref PARTIAL: Regex =
+        Regex::new(r"\bpartial\s+(?:class|struct|interface|record)\b").unwrap();
+    static ref GO_RECEIVER: Regex = Regex::new(r"\bfunc\s*\(([^)]*)\)").unwrap();
+}
+
+/// `public partial class Dog : Animal {`, c# writes `partial` right before the keyword
+fn is_partial(line: &str) -> bool {
+    PARTIAL.is_match(line)
+}
 
-    parent_regex
-        .captures_iter(line)
-        .filter_map(|cap| cap.get(2))
-        .map(|p| p.as_str().to_string())
-        .collect()
+/// bases listed on the definition line, `struct A : B, protected C {`
+///
+/// the access is optional, `struct` and `union` bases are public by default
+fn find_parents(line: &str, kind: ClassKind) -> Vec<(Access, String)> {
+    let bases_regex =
+        Regex::new(r"\b(?:class|struct|union)\s+[\w:]+\s*(?:final\s*)?:\s*([^:{\s][^{]*)").unwrap();
+
+    match bases_regex.captures(line) {
+        Some(caps) => split_top_level(&caps[1], ',')
+            .into_iter()
+            .map(|base| {
+                let words = base.split_whitespace().collect::<Vec<&str>>();
+                let access = words
+                    .iter()
+                    .find_map(|w| Access::from_keyword(w))
+                    .unwrap_or(kind.default_access());
+                let name = words
+                    .iter()
+                    .filter(|w| !["public", "private", "protected", "virtual"].contains(w))
+                    .copied()
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                (access, name)
+            })
+            .filter(|(_, name)| !name.is_empty())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// the type written in front of a tag and the classes its name is qualified with
+///
+/// `int Mammal::getAge(int y) const {` -> `int`, `Mammal`, a constructor has no type
+fn split_definition(line: &str, name: &str) -> (Option<String>, Option<String>) {
+    // the name followed by what opens its parameters, its value or its body
+    let found = word_indices(line, name).find_map(|start| {
+        let after = line[start + name.len()..].trim_start();
+        if !after.starts_with(&['(', '[', ';', '=', ',', '{'][..]) {
+            return None;
+        }
+        // the `a::b::` in front of it, a destructor or a `::name` is not defined here
+        let mut qualifier_start = start;
+        while let Some(before) = line[..qualifier_start].strip_suffix("::") {
+            let outer = before.trim_end_matches(is_word_char);
+            if outer.len() == before.len() {
+                break;
+            }
+            qualifier_start = outer.len();
+        }
+        match line[..qualifier_start].chars().next_back() {
+            Some(c) if is_word_char(c) || c == ':' || c == '~' => None,
+            _ => Some((qualifier_start, start)),
+        }
+    });
+    let (qualifier_start, start) = match found {
+        Some(found) => found,
+        None => return (None, None),
+    };
+    let qualifier = line[qualifier_start..start].trim_end_matches("::");
+
+    let (_, before) = strip_template_clause(&line[..qualifier_start]);
+    let written_type = before
+        .split_whitespace()
+        .filter(|w| !DEFINITION_SPECIFIERS.contains(w) && !w.ends_with(':'))
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    (
+        Some(written_type).filter(|t| !t.is_empty()),
+        Some(qualifier.to_string()).filter(|q| !q.is_empty()),
+    )
+}
+
+/// what `typedef .. name` or `using name = ..;` stands for, as it is written
+fn alias_target<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    if let Some(typedef) = word_indices(line, "typedef").next() {
+        let rest = line[typedef + "typedef".len()..].trim_start();
+        if let Some(end) = word_indices(rest, name).find(|i| *i > 0) {
+            return Some(rest[..end].trim_end());
+        }
+    }
+    let using = word_indices(line, "using").next()?;
+    let rest = line[using + "using".len()..].trim_start();
+    let rest = rest.strip_prefix(name)?.trim_start().strip_prefix('=')?;
+    let target = rest.trim_start().split(';').next()?;
+    Some(target).filter(|t| !t.is_empty())
+}
+
+/// offsets of `word` in `text` where it is not a part of a longer name
+fn word_indices<'a>(text: &'a str, word: &'a str) -> impl Iterator<Item = usize> + 'a {
+    text.match_indices(word).map(|(i, _)| i).filter(move |i| {
+        let before = text[..*i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// parameters of the template a class, function or alias is declared under
+///
+/// ctags `template:` wins, otherwise the `template<..>` clause is looked for on
+/// the definition line and on the line above it
+fn find_template_params(tag: &TagEntry, line: &str, previous_line: &str) -> Vec<String> {
+    if let Some(template) = &tag.template {
+        return strip_template_clause(&format!("template{}", template)).0;
+    }
+    match strip_template_clause(line) {
+        (params, _) if !params.is_empty() => params,
+        _ => strip_template_clause(previous_line).0,
+    }
+}
+
+/// the type a typedef or `using` alias stands for
+///
+/// `typedef struct Node *NodePtr;` -> `Node`, `using Id = unsigned int;` -> `unsigned int`
+fn typedef_target(tag: &TagEntry, line: &str) -> Option<String> {
+    let target = match &tag.typeref {
+        Some(typeref) => typeref_name(typeref).to_string(),
+        None => alias_target(line, &tag.tag_name)?.to_string(),
+    };
+    let target = target
+        .split_whitespace()
+        .filter(|w| !["struct", "union", "enum", "typename", "const"].contains(w))
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .trim_matches(|c| c == '*' || c == '&' || c == ' ')
+        .to_string();
+
+    // the anonymous struct of `typedef struct { .. } Name;`
+    if target.is_empty() || target.starts_with("__anon") {
+        return None;
+    }
+    Some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_type_and_the_qualifier_of_a_definition() {
+        assert_eq!(
+            split_definition("int Mammal::getAge(int y) const {", "getAge"),
+            (Some("int".to_string()), Some("Mammal".to_string()))
+        );
+        assert_eq!(
+            split_definition("static zoo::Dog* a::b::make (", "make"),
+            (Some("zoo::Dog*".to_string()), Some("a::b".to_string()))
+        );
+        assert_eq!(
+            split_definition("Animal::Animal() : age(0) {", "Animal"),
+            (None, Some("Animal".to_string()))
+        );
+        assert_eq!(
+            split_definition("Animal::~Animal() {", "Animal"),
+            (None, None)
+        );
+        assert_eq!(
+            split_definition("int getAgeOf(int y);", "getAge"),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn reads_alias_targets() {
+        assert_eq!(
+            alias_target("typedef struct Node *NodePtr;", "NodePtr"),
+            Some("struct Node *")
+        );
+        assert_eq!(
+            alias_target("using Id = unsigned int;", "Id"),
+            Some("unsigned int")
+        );
+        assert_eq!(alias_target("using namespace std;", "std"), None);
+    }
 }
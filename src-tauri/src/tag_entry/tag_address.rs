@@ -0,0 +1,138 @@
+/// the ex command ctags writes to find a tag again
+#[derive(Debug, PartialEq)]
+pub enum TagAddress {
+    /// content of a `/^...$/` or `?^...$?` search, escapes already removed
+    Pattern(String),
+    /// `42;"`, 1 based
+    Line(usize),
+}
+
+/// parses the address column of a tags file line
+///
+/// `/^class Alive {$/;"` -> `Pattern("class Alive {")`
+///
+/// `42;"` -> `Line(42)`
+pub fn parse_address(column: &str) -> Result<TagAddress, String> {
+    let address = column.strip_suffix(";\"").unwrap_or(column).trim();
+
+    if let Some(delimiter) = address.chars().next().filter(|c| *c == '/' || *c == '?') {
+        return parse_search_pattern(&address[1..], delimiter).map(TagAddress::Pattern);
+    }
+
+    // `42;/pattern/` searches from line 42, the line is enough for us
+    let line_str = address.split(';').next().unwrap_or("");
+    if !line_str.is_empty() && line_str.chars().all(|c| c.is_ascii_digit()) {
+        return match line_str.parse::<usize>() {
+            Ok(0) => Err("line number 0 in address".to_string()),
+            Ok(line) => Ok(TagAddress::Line(line)),
+            Err(e) => Err(format!("invalid line number {} : {}", line_str, e)),
+        };
+    }
+
+    if address.is_empty() {
+        return Err("empty address".to_string());
+    }
+    Err(format!("unrecognised address {}", address))
+}
+
+/// reads up to the closing `delimiter`, `\\` and `\<delimiter>` are the only
+/// escapes ctags writes, anything else after a `\` is kept as is
+fn parse_search_pattern(body: &str, delimiter: char) -> Result<String, String> {
+    let mut content = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    let mut closed = false;
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(e) if e == '\\' || e == delimiter => content.push(e),
+                Some(e) => {
+                    content.push('\\');
+                    content.push(e);
+                }
+                None => return Err("pattern ends with a lone \\".to_string()),
+            }
+            continue;
+        }
+        if c == delimiter {
+            closed = true;
+            break;
+        }
+        // only a `$` right before the delimiter anchors the end of the line
+        if c == '$' && chars.clone().next() == Some(delimiter) {
+            continue;
+        }
+        content.push(c);
+    }
+
+    if !closed {
+        return Err(format!("pattern is not closed by {}", delimiter));
+    }
+    if !chars.as_str().trim().is_empty() {
+        return Err(format!("unexpected text after pattern {}", chars.as_str()));
+    }
+
+    let content = content.strip_prefix('^').unwrap_or(&content);
+    Ok(content.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_search_patterns() {
+        assert_eq!(
+            parse_address("/^class Alive {$/;\""),
+            Ok(TagAddress::Pattern("class Alive {".to_string()))
+        );
+        assert_eq!(
+            parse_address("?^int main()$?;\""),
+            Ok(TagAddress::Pattern("int main()".to_string()))
+        );
+    }
+
+    #[test]
+    fn reads_line_numbers() {
+        assert_eq!(parse_address("42;\""), Ok(TagAddress::Line(42)));
+        assert_eq!(parse_address("42;/pattern/;\""), Ok(TagAddress::Line(42)));
+        assert!(parse_address("0;\"").is_err());
+        assert!(parse_address(";\"").is_err());
+    }
+
+    #[test]
+    fn removes_escapes() {
+        assert_eq!(
+            parse_search_pattern("^a \\/ b$/", '/'),
+            Ok("a / b".to_string())
+        );
+        assert_eq!(
+            parse_search_pattern("^path \\\\ sep$/", '/'),
+            Ok("path \\ sep".to_string())
+        );
+        // only the delimiter and `\` are escaped
+        assert_eq!(
+            parse_search_pattern("^a \\n b$/", '/'),
+            Ok("a \\n b".to_string())
+        );
+        assert!(parse_search_pattern("^a \\", '/').is_err());
+    }
+
+    #[test]
+    fn keeps_a_dollar_not_ending_the_line() {
+        assert_eq!(
+            parse_search_pattern("^int $x;$/", '/'),
+            Ok("int $x;".to_string())
+        );
+        assert_eq!(
+            parse_search_pattern("^price$$/", '/'),
+            Ok("price$".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_patterns() {
+        assert!(parse_search_pattern("^class A {$", '/').is_err());
+        assert!(parse_search_pattern("^class A {$/ extra", '/').is_err());
+    }
+}
@@ -0,0 +1,313 @@
+/// words that change how a type is held but not which type it is
+const QUALIFIERS: [&str; 8] = [
+    "const", "volatile", "mutable", "static", "typename", "struct", "union", "enum",
+];
+
+/// a type as it is written in code, `std::map<int, Animal*>` is `std::map`
+/// with the arguments `int` and `Animal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeRef {
+    pub name: String,
+    pub args: Vec<TypeRef>,
+}
+
+/// parses a type reference, the qualifiers, pointers and references are dropped
+///
+/// `const std::vector<Animal*>&` -> `std::vector` of `[Animal]`
+pub fn parse_type(text: &str) -> TypeRef {
+    let text = text.trim();
+    let (head, args) = match text.find('<') {
+        Some(open) => {
+            let close = matching_angle(text, open).unwrap_or(text.len());
+            let inside = text.get(open + 1..close).unwrap_or("");
+            let args = split_top_level(inside, ',')
+                .into_iter()
+                .filter(|a| !a.trim().is_empty())
+                .map(parse_type)
+                .collect();
+            (&text[..open], args)
+        }
+        None => (text, Vec::new()),
+    };
+    let name = head
+        .split(|c: char| c.is_whitespace() || c == '*' || c == '&')
+        .filter(|w| !w.is_empty() && !QUALIFIERS.contains(w))
+        .collect::<Vec<&str>>()
+        .join(" ");
+    TypeRef { name, args }
+}
+
+/// `std::vector<Animal>` -> `std::vector`
+pub fn base_name(text: &str) -> String {
+    parse_type(text).name
+}
+
+/// every type named inside the template arguments, the nested ones too
+///
+/// `std::map<int, std::vector<Animal>>` -> `int`, `std::vector`, `Animal`,
+/// values like the `3` of `std::array<int, 3>` are left out
+pub fn generic_args(text: &str) -> Vec<String> {
+    fn collect(t: &TypeRef, res: &mut Vec<String>) {
+        for a in &t.args {
+            let is_value = a.name.is_empty() || a.name.starts_with(|c: char| c.is_ascii_digit());
+            if !is_value && !res.contains(&a.name) {
+                res.push(a.name.clone());
+            }
+            collect(a, res);
+        }
+    }
+    let mut res = Vec::new();
+    collect(&parse_type(text), &mut res);
+    res
+}
+
+/// splits at the `sep` chars that are not inside `<>`, `()`, `[]` or `{}`
+///
+/// `Base<A, B>, Other` -> `Base<A, B>`, ` Other`
+pub fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut part_start = 0;
+    for (b_i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ if c == sep && depth <= 0 => {
+                parts.push(&text[part_start..b_i]);
+                part_start = b_i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[part_start..]);
+    parts
+}
+
+/// names of the parameters of a leading `template<..>` clause and the text after it
+///
+/// `template<typename T, int N = 3> class Array` -> `[T, N]`, `class Array`
+pub fn strip_template_clause(text: &str) -> (Vec<String>, &str) {
+    let trimmed = text.trim_start();
+    let rest = match trimmed.strip_prefix("template") {
+        Some(rest) if rest.trim_start().starts_with('<') => rest.trim_start(),
+        _ => return (Vec::new(), text),
+    };
+    let close = match matching_angle(rest, 0) {
+        Some(close) => close,
+        None => return (Vec::new(), text),
+    };
+    let params = split_top_level(&rest[1..close], ',')
+        .into_iter()
+        .filter_map(template_param_name)
+        .collect();
+    (params, rest[close + 1..].trim_start())
+}
+
+/// names of the generic parameters, the lifetimes are left out
+///
+/// `<'a, T: Display + 'a, const N: usize>` -> `[T, N]`, the bounds of java
+/// `<T extends Animal>`, kotlin `<reified T : Animal>` and go `[K comparable]`
+/// are left out too
+pub fn generic_params(generics: &str) -> Vec<String> {
+    let inside = generics.trim();
+    let inside = inside
+        .strip_prefix('<')
+        .or(inside.strip_prefix('['))
+        .unwrap_or(inside);
+    let inside = inside
+        .strip_suffix('>')
+        .or(inside.strip_suffix(']'))
+        .unwrap_or(inside);
+    split_top_level(inside, ',')
+        .into_iter()
+        .filter_map(|param| {
+            let param = param.trim();
+            let param = ["const ", "reified ", "in ", "out "]
+                .iter()
+                .fold(param, |p, prefix| p.strip_prefix(prefix).unwrap_or(p));
+            let name = param.split(|c: char| c == ':' || c == '=').next()?;
+            let name = name.split_whitespace().next().unwrap_or("");
+            if name.is_empty() || name.starts_with('\'') {
+                return None;
+            }
+            Some(name.to_string())
+        })
+        .collect()
+}
+
+/// the types of the parameters and of the results of a go function, without
+/// their names and packages
+///
+/// `(ctx context.Context, a, b int)` and `(n int, err error)` ->
+/// `(Context, int, int) (int, error)`
+pub fn go_signature(params: &str, results: &str) -> String {
+    fn words(p: &str) -> Vec<&str> {
+        split_top_level(p, ' ')
+            .into_iter()
+            .filter(|w| !w.is_empty())
+            .collect()
+    }
+    fn types(list: &str) -> Vec<String> {
+        let parts = split_top_level(list, ',')
+            .into_iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<&str>>();
+        // every parameter is named or none is, `a, b int` are two ints
+        let named = parts.iter().any(|p| words(p).len() > 1);
+        let mut types = Vec::new();
+        let mut untyped = 0;
+        for p in parts {
+            let p_words = words(p);
+            match named {
+                true if p_words.len() == 1 => untyped += 1,
+                true => {
+                    let type_ = without_packages(&p_words[1..].join(" "));
+                    types.extend(std::iter::repeat(type_).take(untyped + 1));
+                    untyped = 0;
+                }
+                false => types.push(without_packages(p)),
+            }
+        }
+        types
+    }
+    let results = results.trim();
+    let results = match results.strip_prefix('(') {
+        Some(list) => types(list.strip_suffix(')').unwrap_or(list)),
+        None if results.is_empty() => Vec::new(),
+        None => vec![without_packages(results)],
+    };
+    format!("({}) ({})", types(params).join(", "), results.join(", "))
+}
+
+/// `map[string]*m.Dog` -> `map[string]*Dog`
+fn without_packages(type_: &str) -> String {
+    let mut res = String::new();
+    let mut word = String::new();
+    for c in type_.chars() {
+        match c {
+            c if c.is_alphanumeric() || c == '_' => word.push(c),
+            // `m` of `m.Dog` is the package the type comes from
+            '.' if !word.is_empty() => word.clear(),
+            _ => {
+                res.push_str(&word);
+                word.clear();
+                res.push(c);
+            }
+        }
+    }
+    res.push_str(&word);
+    res
+}
+
+/// `typename T` / `class T = int` / `int N` / `typename... Ts` -> the name
+fn template_param_name(param: &str) -> Option<String> {
+    let declaration = split_top_level(param, '=')[0];
+    let name = declaration
+        .trim()
+        .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()?;
+    // `template<typename>` declares an unnamed parameter
+    if name.is_empty() || ["typename", "class"].contains(&name) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// offset of the `>` closing the `<` at `open`
+pub fn matching_angle(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (b_i, c) in text[open..].char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + b_i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str, args: Vec<TypeRef>) -> TypeRef {
+        TypeRef {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn drops_qualifiers_pointers_and_references() {
+        assert_eq!(
+            parse_type("const std::vector<Animal*>&"),
+            named("std::vector", vec![named("Animal", vec![])])
+        );
+        assert_eq!(base_name("volatile struct Node *"), "Node");
+    }
+
+    #[test]
+    fn parses_nested_arguments() {
+        assert_eq!(
+            parse_type("std::map<int, std::vector<Animal>>"),
+            named(
+                "std::map",
+                vec![
+                    named("int", vec![]),
+                    named("std::vector", vec![named("Animal", vec![])]),
+                ]
+            )
+        );
+        assert_eq!(
+            generic_args("std::map<int, std::vector<Animal>>"),
+            vec!["int", "std::vector", "Animal"]
+        );
+        assert_eq!(generic_args("std::array<int, 3>"), vec!["int"]);
+    }
+
+    #[test]
+    fn splits_outside_brackets() {
+        assert_eq!(
+            split_top_level("Base<A, B>, Other", ','),
+            vec!["Base<A, B>", " Other"]
+        );
+        assert_eq!(matching_angle("a<b<c>>d", 1), Some(6));
+        assert_eq!(matching_angle("a<b<c>", 1), None);
+    }
+
+    #[test]
+    fn reads_template_clauses() {
+        assert_eq!(
+            strip_template_clause("template<typename T, int N = 3> class Array"),
+            (vec!["T".to_string(), "N".to_string()], "class Array")
+        );
+        assert_eq!(
+            strip_template_clause("template<typename> struct Tag").0,
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            generic_params("<'a, T: Display + 'a, const N: usize>"),
+            vec!["T", "N"]
+        );
+        assert_eq!(generic_params("[K comparable, V any]"), vec!["K", "V"]);
+    }
+
+    #[test]
+    fn reads_go_signatures() {
+        assert_eq!(
+            go_signature("ctx context.Context, d *m.Dog", "error"),
+            "(Context, *Dog) (error)"
+        );
+        assert_eq!(
+            go_signature("a, b int", "(n int, err error)"),
+            "(int, int) (int, error)"
+        );
+        assert_eq!(go_signature("int, string", ""), "(int, string) ()");
+    }
+}